@@ -1,19 +1,37 @@
-use astroport_ibc::TIMEOUT_LIMITS;
+use astroport_ibc::{SIGNAL_OUTAGE_LIMITS, TIMEOUT_LIMITS};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, IbcMsg, IbcTimeout,
-    MessageInfo, Response, StdError, SubMsg,
+    attr, to_json_binary, to_json_vec, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, IbcMsg,
+    IbcTimeout, MessageInfo, Response, StdError, StdResult, Storage, SubMsg, Timestamp,
 };
 use cw2::{get_contract_version, set_contract_version};
 use ibc_controller_package::astroport_governance::assembly::ProposalStatus;
 
-use astro_satellite_package::SatelliteMsg;
+use astro_satellite_package::{IbcVersion, SatelliteMsg};
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
-use ibc_controller_package::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use ibc_controller_package::{
+    BatchProposal, ChannelReports, ConfigResponse, ExecuteMsg, InstantiateMsg,
+    OwnershipProposalResponse, ProposalDispatch, QueryMsg, SatelliteInfo, SimulationInfo,
+    SimulationStatus,
+};
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG, LAST_ERROR, OWNERSHIP_PROPOSAL, PROPOSAL_STATE};
+use crate::ibc::confirm_assembly;
+use crate::migration::{
+    migrate_channels, migrate_config, migrate_last_error, migrate_proposal_index,
+};
+use crate::state::{
+    abandon_dispatch, abandon_dispatches, channel_version, check_satellite_channel,
+    list_all_satellites, list_channels, list_errors, list_proposals, list_reports, list_satellites,
+    list_simulations, load_enabled_satellite, load_proposal, proposal_channels, proposal_counts,
+    proposal_errors, record_heartbeat, satellites_health, save_dispatch, save_proposal_status,
+    Config, CHANNELS, CONFIG, DEFAULT_HEARTBEAT_INTERVAL, DEFAULT_MAX_PROPOSAL_ATTEMPTS,
+    DEFAULT_OUTAGE_WARNING_MARGIN, LAST_ERROR, LAST_SIMULATIONS, OWNERSHIP_PROPOSAL,
+    PROPOSAL_DISPATCHES, PROPOSAL_MESSAGES, PROPOSAL_MODES, PROPOSAL_STATE, REPORTS,
+    RETRYABLE_DISPATCHES, SATELLITES, SATELLITE_CHANNELS, SIMULATIONS, SIMULATION_COUNT,
+    SIMULATION_INTERVAL,
+};
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -36,6 +54,12 @@ pub fn instantiate(
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
             timeout: msg.timeout,
+            heartbeat_timeout: msg.timeout,
+            max_proposal_attempts: DEFAULT_MAX_PROPOSAL_ATTEMPTS,
+            retry_error_acks: false,
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            max_chunk_size: 0,
+            epoch: 0,
         },
     )?;
     Ok(Response::new().add_attribute("action", "instantiate"))
@@ -52,32 +76,78 @@ pub fn execute(
 
     match msg {
         ExecuteMsg::IbcExecuteProposal {
+            satellite,
             channel_id,
             proposal_id,
             messages,
+            timeout,
+            mode,
         } => {
             if config.owner != info.sender {
                 return Err(ContractError::Unauthorized {});
             }
 
-            if PROPOSAL_STATE.has(deps.storage, proposal_id) {
-                return Err(ContractError::ProposalAlreadyExists { proposal_id });
+            let satellite = resolve_satellite(deps.storage, satellite, channel_id)?;
+            execute_proposal(
+                deps,
+                env,
+                config,
+                vec![satellite],
+                BatchProposal {
+                    id: proposal_id,
+                    messages,
+                    mode: mode.unwrap_or_default(),
+                },
+                timeout,
+            )
+        }
+        ExecuteMsg::IbcExecuteProposalMulti {
+            satellites,
+            proposal_id,
+            messages,
+            timeout,
+            mode,
+        } => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
             }
 
-            let ibc_msg = CosmosMsg::Ibc(IbcMsg::SendPacket {
-                channel_id: channel_id.clone(),
-                data: to_json_binary(&SatelliteMsg::ExecuteProposal {
+            execute_proposal(
+                deps,
+                env,
+                config,
+                satellites,
+                BatchProposal {
                     id: proposal_id,
                     messages,
-                })?,
-                timeout: IbcTimeout::from(env.block.time.plus_seconds(config.timeout)),
-            });
-            PROPOSAL_STATE.save(deps.storage, proposal_id, &ProposalStatus::InProgress {})?;
+                    mode: mode.unwrap_or_default(),
+                },
+                timeout,
+            )
+        }
+        ExecuteMsg::IbcExecuteBatch {
+            satellite,
+            proposals,
+            timeout,
+        } => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
 
-            Ok(Response::new()
-                .add_message(ibc_msg)
-                .add_attribute("action", "ibc_execute")
-                .add_attribute("channel", channel_id))
+            execute_batch(deps, env, config, satellite, proposals, timeout)
+        }
+        ExecuteMsg::IbcSimulateProposal {
+            satellite,
+            messages,
+            timeout,
+        } => {
+            // The owner is not rate limited
+            if config.owner != info.sender {
+                let channel_id = load_enabled_satellite(deps.storage, &satellite)?.channel_id;
+                record_simulation(deps.storage, &channel_id, env.block.time)?;
+            }
+
+            simulate_proposal(deps, env, config, satellite, messages, timeout)
         }
         ExecuteMsg::UpdateTimeout { new_timeout } => {
             if config.owner != info.sender {
@@ -96,6 +166,56 @@ pub fn execute(
                 .add_attribute("action", "update_timeout")
                 .add_attribute("timeout", new_timeout.to_string()))
         }
+        ExecuteMsg::UpdateConfig(params) => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let mut new_config = config.clone();
+            new_config.update(params)?;
+            CONFIG.save(deps.storage, &new_config)?;
+
+            // Failed proposals may no longer be retryable under the stricter retry limits.
+            // Other updates don't walk through the failed proposals
+            let completed = if new_config.max_proposal_attempts < config.max_proposal_attempts
+                || (config.retry_error_acks && !new_config.retry_error_acks)
+            {
+                abandon_dispatches(deps.storage, |_, attempts, timed_out| {
+                    !new_config.can_retry(attempts, timed_out)
+                })?
+            } else {
+                vec![]
+            };
+
+            Ok(Response::new()
+                .add_submessages(confirm_completed(&new_config, completed)?)
+                .add_attribute("action", "update_config"))
+        }
+        ExecuteMsg::RetryProposal { id, channel } => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            retry_proposal(deps, env, config, id, channel)
+        }
+        ExecuteMsg::AbandonProposal { id, channel } => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+            if !RETRYABLE_DISPATCHES.has(deps.storage, (id, &channel)) {
+                return Err(ContractError::ProposalNotRetryable { id, channel });
+            }
+
+            let completed = abandon_dispatch(deps.storage, id, &channel)?
+                .map(|status| vec![(id, status)])
+                .unwrap_or_default();
+
+            Ok(Response::new()
+                .add_submessages(confirm_completed(&config, completed)?)
+                .add_attribute("action", "abandon_proposal")
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("channel", channel))
+        }
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => propose_new_owner(
             deps,
             info,
@@ -121,42 +241,739 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
-        ExecuteMsg::SendHeartbeat { channels } => {
+        ExecuteMsg::SendHeartbeat {
+            mut satellites,
+            channels,
+        } => {
+            // The owner is not rate limited
+            let interval = if info.sender == config.owner {
+                0
+            } else {
+                config.heartbeat_interval
+            };
             let mut res = Response::new().add_attribute("action", "send_heartbeat");
 
-            for channel in channels {
-                let ibc_msg = CosmosMsg::Ibc(IbcMsg::SendPacket {
-                    channel_id: channel.clone(),
-                    data: to_json_binary(&SatelliteMsg::Heartbeat {})?,
-                    timeout: IbcTimeout::from(env.block.time.plus_seconds(config.timeout)),
-                });
-                res.messages.push(SubMsg::new(ibc_msg));
+            for channel_id in channels {
+                satellites.push(resolve_satellite(
+                    deps.storage,
+                    String::new(),
+                    Some(channel_id),
+                )?);
+            }
+            for satellite in satellites {
+                let channel = load_enabled_satellite(deps.storage, &satellite)?.channel_id;
+                record_heartbeat(deps.storage, &channel, env.block.time, interval)?;
+                res.messages.push(SubMsg::new(heartbeat_msg(
+                    deps.storage,
+                    &env,
+                    &config,
+                    &channel,
+                )?));
                 res.attributes.push(attr("channel", channel));
             }
 
             Ok(res)
         }
+        ExecuteMsg::SendHeartbeats {} => {
+            let mut res = Response::new().add_attribute("action", "send_heartbeats");
+
+            for satellite in list_all_satellites(deps.storage)? {
+                if !satellite.enabled || !CHANNELS.has(deps.storage, &satellite.channel_id) {
+                    continue;
+                }
+
+                let channel = satellite.channel_id;
+                match record_heartbeat(
+                    deps.storage,
+                    &channel,
+                    env.block.time,
+                    config.heartbeat_interval,
+                ) {
+                    Ok(()) => {}
+                    Err(ContractError::HeartbeatTooEarly { .. }) => continue,
+                    Err(err) => return Err(err),
+                }
+                res.messages.push(SubMsg::new(heartbeat_msg(
+                    deps.storage,
+                    &env,
+                    &config,
+                    &channel,
+                )?));
+                res.attributes.push(attr("channel", channel));
+            }
+
+            if res.messages.is_empty() {
+                return Err(ContractError::NoHeartbeatsDue {});
+            }
+
+            Ok(res)
+        }
+        ExecuteMsg::RegisterSatellite {
+            alias,
+            channel_id,
+            port_id,
+            connection_id,
+            max_signal_outage,
+            timeout,
+        } => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+            if alias.is_empty() {
+                return Err(ContractError::InvalidSatelliteAlias {});
+            }
+            if !SIGNAL_OUTAGE_LIMITS.contains(&max_signal_outage) {
+                return Err(ContractError::SignalOutageLimitsError {});
+            }
+            validate_timeout(timeout)?;
+            if SATELLITES.has(deps.storage, &alias) {
+                return Err(ContractError::SatelliteAlreadyExists { alias });
+            }
+            assert_channel_is_free(deps.storage, &channel_id)?;
+
+            let satellite = SatelliteInfo {
+                alias: alias.clone(),
+                channel_id: channel_id.clone(),
+                port_id,
+                connection_id,
+                enabled: true,
+                max_signal_outage,
+                timeout,
+            };
+            // Channels opened after the registration are checked on dispatch
+            if let Some(channel) = CHANNELS.may_load(deps.storage, &channel_id)? {
+                check_satellite_channel(&satellite, &channel)?;
+            }
+
+            SATELLITE_CHANNELS.save(deps.storage, &channel_id, &alias)?;
+            SATELLITES.save(deps.storage, &alias, &satellite)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "register_satellite")
+                .add_attribute("satellite", alias)
+                .add_attribute("channel", channel_id))
+        }
+        ExecuteMsg::UpdateSatellite {
+            alias,
+            channel_id,
+            port_id,
+            connection_id,
+            enabled,
+            max_signal_outage,
+            timeout,
+            clear_timeout,
+        } => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let mut satellite = SATELLITES.may_load(deps.storage, &alias)?.ok_or_else(|| {
+                ContractError::SatelliteNotFound {
+                    alias: alias.clone(),
+                }
+            })?;
+
+            let old_channel = satellite.channel_id.clone();
+            if let Some(channel_id) = channel_id {
+                if channel_id != satellite.channel_id {
+                    assert_channel_is_free(deps.storage, &channel_id)?;
+                    SATELLITE_CHANNELS.remove(deps.storage, &satellite.channel_id);
+                    SATELLITE_CHANNELS.save(deps.storage, &channel_id, &alias)?;
+                    satellite.channel_id = channel_id;
+                }
+            }
+            if let Some(port_id) = port_id {
+                satellite.port_id = port_id;
+            }
+            if let Some(connection_id) = connection_id {
+                satellite.connection_id = connection_id;
+            }
+            if let Some(enabled) = enabled {
+                satellite.enabled = enabled;
+            }
+            if let Some(max_signal_outage) = max_signal_outage {
+                if !SIGNAL_OUTAGE_LIMITS.contains(&max_signal_outage) {
+                    return Err(ContractError::SignalOutageLimitsError {});
+                }
+                satellite.max_signal_outage = max_signal_outage;
+            }
+            match (timeout, clear_timeout == Some(true)) {
+                (Some(_), true) => return Err(ContractError::ConflictingTimeoutUpdate {}),
+                (None, true) => satellite.timeout = None,
+                (Some(_), false) => {
+                    validate_timeout(timeout)?;
+                    satellite.timeout = timeout;
+                }
+                (None, false) => {}
+            }
+            if let Some(channel) = CHANNELS.may_load(deps.storage, &satellite.channel_id)? {
+                check_satellite_channel(&satellite, &channel)?;
+            }
+
+            SATELLITES.save(deps.storage, &alias, &satellite)?;
+
+            // Proposals can't be retried via a disabled satellite or a replaced channel
+            let completed = if !satellite.enabled || old_channel != satellite.channel_id {
+                abandon_dispatches(deps.storage, |channel_id, _, _| channel_id == old_channel)?
+            } else {
+                vec![]
+            };
+
+            Ok(Response::new()
+                .add_submessages(confirm_completed(&config, completed)?)
+                .add_attribute("action", "update_satellite")
+                .add_attribute("satellite", alias))
+        }
+        ExecuteMsg::RemoveSatellite { alias } => {
+            if config.owner != info.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let satellite = SATELLITES.may_load(deps.storage, &alias)?.ok_or_else(|| {
+                ContractError::SatelliteNotFound {
+                    alias: alias.clone(),
+                }
+            })?;
+            SATELLITE_CHANNELS.remove(deps.storage, &satellite.channel_id);
+            SATELLITES.remove(deps.storage, &alias);
+
+            let completed = abandon_dispatches(deps.storage, |channel_id, _, _| {
+                channel_id == satellite.channel_id
+            })?;
+
+            Ok(Response::new()
+                .add_submessages(confirm_completed(&config, completed)?)
+                .add_attribute("action", "remove_satellite")
+                .add_attribute("satellite", alias))
+        }
+    }
+}
+
+/// Sends the proposal to every specified satellite and tracks its state per channel
+fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    satellites: Vec<String>,
+    proposal: BatchProposal,
+    timeout: Option<u64>,
+) -> Result<Response, ContractError> {
+    validate_timeout(timeout)?;
+    let proposal_id = proposal.id;
+
+    if satellites.is_empty() {
+        return Err(ContractError::NoSatellites {});
+    }
+
+    if PROPOSAL_STATE.has(deps.storage, proposal_id) {
+        return Err(ContractError::ProposalAlreadyExists { proposal_id });
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "ibc_execute")
+        .add_attribute("proposal_id", proposal_id.to_string());
+
+    for (i, satellite) in satellites.iter().enumerate() {
+        if satellites[..i].contains(satellite) {
+            return Err(ContractError::DuplicateSatellite {
+                alias: satellite.clone(),
+            });
+        }
+
+        let satellite_info = load_enabled_satellite(deps.storage, satellite)?;
+        let timeout_at = env
+            .block
+            .time
+            .plus_seconds(config.proposal_timeout(&satellite_info, timeout));
+        let channel_id = satellite_info.channel_id;
+        save_new_dispatch(
+            deps.storage,
+            &env,
+            proposal_id,
+            &channel_id,
+            timeout_at,
+            timeout,
+        )?;
+
+        res = res
+            .add_messages(proposal_packets(
+                deps.storage,
+                &env,
+                &config,
+                &channel_id,
+                &proposal,
+                timeout_at,
+                1,
+            )?)
+            .add_attribute("satellite", satellite)
+            .add_attribute("channel", channel_id);
+    }
+    save_proposal_status(deps.storage, proposal_id, &ProposalStatus::InProgress {})?;
+    PROPOSAL_MESSAGES.save(deps.storage, proposal_id, &proposal.messages)?;
+    PROPOSAL_MODES.save(deps.storage, proposal_id, &proposal.mode)?;
+
+    Ok(res)
+}
+
+/// Sends several proposals to the satellite with a single packet.
+/// Each proposal is tracked on its own as if it was sent separately
+fn execute_batch(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    satellite: String,
+    proposals: Vec<BatchProposal>,
+    timeout: Option<u64>,
+) -> Result<Response, ContractError> {
+    validate_timeout(timeout)?;
+
+    if proposals.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+
+    let satellite_info = load_enabled_satellite(deps.storage, &satellite)?;
+    let timeout_at = env
+        .block
+        .time
+        .plus_seconds(config.proposal_timeout(&satellite_info, timeout));
+    let channel_id = satellite_info.channel_id;
+
+    let mut res = Response::new()
+        .add_attribute("action", "ibc_execute_batch")
+        .add_attribute("satellite", satellite)
+        .add_attribute("channel", &channel_id);
+
+    for (i, proposal) in proposals.iter().enumerate() {
+        let proposal_id = proposal.id;
+        if PROPOSAL_STATE.has(deps.storage, proposal_id)
+            || proposals[..i].iter().any(|prev| prev.id == proposal_id)
+        {
+            return Err(ContractError::ProposalAlreadyExists { proposal_id });
+        }
+    }
+
+    for proposal in &proposals {
+        let proposal_id = proposal.id;
+        save_new_dispatch(
+            deps.storage,
+            &env,
+            proposal_id,
+            &channel_id,
+            timeout_at,
+            timeout,
+        )?;
+        save_proposal_status(deps.storage, proposal_id, &ProposalStatus::InProgress {})?;
+        PROPOSAL_MESSAGES.save(deps.storage, proposal_id, &proposal.messages)?;
+        PROPOSAL_MODES.save(deps.storage, proposal_id, &proposal.mode)?;
+
+        res = res.add_attribute("proposal_id", proposal_id.to_string());
+    }
+
+    let packet = SatelliteMsg::ExecuteBatch {
+        proposals,
+        sent_at: env.block.time,
+        epoch: config.epoch,
+    };
+
+    Ok(res.add_message(CosmosMsg::Ibc(IbcMsg::SendPacket {
+        data: packet.encode(channel_version(deps.storage, &channel_id)?)?,
+        channel_id,
+        timeout: IbcTimeout::from(timeout_at),
+    })))
+}
+
+/// Records the time of a simulation sent by anyone but the owner.
+/// Fails if [`SIMULATION_INTERVAL`] seconds haven't passed since the previous one
+fn record_simulation(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    now: Timestamp,
+) -> Result<(), ContractError> {
+    if let Some(last_simulation) = LAST_SIMULATIONS.may_load(storage, channel_id)? {
+        let next_simulation = last_simulation.plus_seconds(SIMULATION_INTERVAL);
+        if next_simulation > now {
+            return Err(ContractError::SimulationTooEarly {
+                channel_id: channel_id.to_string(),
+                next_simulation,
+            });
+        }
+    }
+
+    LAST_SIMULATIONS.save(storage, channel_id, &now)?;
+
+    Ok(())
+}
+
+/// Sends the messages to the satellite to be executed and reverted right away
+fn simulate_proposal(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    satellite: String,
+    messages: Vec<CosmosMsg>,
+    timeout: Option<u64>,
+) -> Result<Response, ContractError> {
+    validate_timeout(timeout)?;
+
+    let satellite_info = load_enabled_satellite(deps.storage, &satellite)?;
+    let timeout_at = env
+        .block
+        .time
+        .plus_seconds(config.proposal_timeout(&satellite_info, timeout));
+    let channel_id = satellite_info.channel_id;
+
+    let id = SIMULATION_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SIMULATION_COUNT.save(deps.storage, &id)?;
+
+    let packet = SatelliteMsg::SimulateProposal {
+        id,
+        messages: messages.clone(),
+    };
+    let data = packet.encode(channel_version(deps.storage, &channel_id)?)?;
+
+    SIMULATIONS.save(
+        deps.storage,
+        id,
+        &SimulationInfo {
+            id,
+            satellite: satellite.clone(),
+            channel_id: channel_id.clone(),
+            messages,
+            sent_at: env.block.time,
+            status: SimulationStatus::Pending,
+            finished_at: None,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Ibc(IbcMsg::SendPacket {
+            channel_id: channel_id.clone(),
+            data,
+            timeout: IbcTimeout::from(timeout_at),
+        }))
+        .set_data(to_json_binary(&id)?)
+        .add_attribute("action", "ibc_simulate_proposal")
+        .add_attribute("satellite", satellite)
+        .add_attribute("channel", channel_id)
+        .add_attribute("simulation_id", id.to_string()))
+}
+
+/// Starts tracking the first attempt to relay the proposal to the satellite channel
+fn save_new_dispatch(
+    storage: &mut dyn Storage,
+    env: &Env,
+    proposal_id: u64,
+    channel_id: &str,
+    timeout_at: Timestamp,
+    timeout: Option<u64>,
+) -> StdResult<()> {
+    save_dispatch(
+        storage,
+        proposal_id,
+        &ProposalDispatch {
+            channel_id: channel_id.to_string(),
+            status: ProposalStatus::InProgress {},
+            sent_height: env.block.height,
+            sent_at: env.block.time,
+            timeout_at,
+            finished_at: None,
+            error: None,
+            attempts: 1,
+            retryable: false,
+            timeout,
+            receipt: None,
+        },
+    )
+}
+
+/// Sends the stored proposal messages to the satellite channel again
+fn retry_proposal(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    id: u64,
+    channel: String,
+) -> Result<Response, ContractError> {
+    let mut dispatch = PROPOSAL_DISPATCHES
+        .may_load(deps.storage, (id, &channel))?
+        .ok_or_else(|| ContractError::ProposalDispatchNotFound {
+            id,
+            channel: channel.clone(),
+        })?;
+
+    // The attempts limit might have been lowered since the packet failed
+    if !dispatch.retryable || dispatch.attempts >= config.max_proposal_attempts {
+        return Err(ContractError::ProposalNotRetryable { id, channel });
+    }
+
+    // The satellite must still be served by this channel
+    let alias = SATELLITE_CHANNELS
+        .may_load(deps.storage, &channel)?
+        .ok_or_else(|| ContractError::ProposalNotRetryable {
+            id,
+            channel: channel.clone(),
+        })?;
+    let satellite = load_enabled_satellite(deps.storage, &alias)?;
+
+    let proposal = BatchProposal {
+        id,
+        messages: PROPOSAL_MESSAGES.load(deps.storage, id)?,
+        mode: PROPOSAL_MODES
+            .may_load(deps.storage, id)?
+            .unwrap_or_default(),
+    };
+    let timeout_at = env
+        .block
+        .time
+        .plus_seconds(config.proposal_timeout(&satellite, dispatch.timeout));
+
+    dispatch.status = ProposalStatus::InProgress;
+    dispatch.sent_height = env.block.height;
+    dispatch.sent_at = env.block.time;
+    dispatch.timeout_at = timeout_at;
+    dispatch.finished_at = None;
+    dispatch.error = None;
+    dispatch.attempts += 1;
+    dispatch.retryable = false;
+    dispatch.receipt = None;
+    save_dispatch(deps.storage, id, &dispatch)?;
+    RETRYABLE_DISPATCHES.remove(deps.storage, (id, &channel));
+
+    Ok(Response::new()
+        .add_messages(proposal_packets(
+            deps.storage,
+            &env,
+            &config,
+            &channel,
+            &proposal,
+            timeout_at,
+            dispatch.attempts,
+        )?)
+        .add_attribute("action", "retry_proposal")
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("satellite", alias)
+        .add_attribute("channel", channel)
+        .add_attribute("attempt", dispatch.attempts.to_string()))
+}
+
+/// Builds the Assembly callbacks for the proposals completed without a packet outcome
+fn confirm_completed(
+    config: &Config,
+    completed: Vec<(u64, ProposalStatus)>,
+) -> StdResult<Vec<SubMsg>> {
+    completed
+        .into_iter()
+        .map(|(id, status)| confirm_assembly(&config.owner, id, status))
+        .collect()
+}
+
+/// Builds the proposal packets for the satellite channel.
+/// Proposals larger than the max chunk size are split into chunks on channels supporting them.
+/// Chunks are tagged with the dispatch attempt they belong to
+fn proposal_packets(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    channel_id: &str,
+    proposal: &BatchProposal,
+    timeout_at: Timestamp,
+    attempt: u32,
+) -> StdResult<Vec<CosmosMsg>> {
+    let BatchProposal { id, messages, mode } = proposal;
+    let version = channel_version(storage, channel_id)?;
+    let chunk_size = config.max_chunk_size as usize;
+
+    let packets =
+        if version >= IbcVersion::V2 && chunk_size > 0 && to_json_vec(messages)?.len() > chunk_size
+        {
+            SatelliteMsg::chunks(
+                *id,
+                messages,
+                chunk_size,
+                env.block.time,
+                config.epoch,
+                mode.clone(),
+                attempt,
+            )?
+        } else {
+            vec![SatelliteMsg::ExecuteProposal {
+                id: *id,
+                messages: messages.clone(),
+                sent_at: Some(env.block.time),
+                epoch: Some(config.epoch),
+                mode: mode.clone(),
+            }]
+        };
+
+    packets
+        .into_iter()
+        .map(|packet| {
+            Ok(CosmosMsg::Ibc(IbcMsg::SendPacket {
+                channel_id: channel_id.to_string(),
+                data: packet.encode(version)?,
+                timeout: IbcTimeout::from(timeout_at),
+            }))
+        })
+        .collect()
+}
+
+/// Builds a heartbeat packet for the satellite channel
+fn heartbeat_msg(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    channel_id: &str,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Ibc(IbcMsg::SendPacket {
+        channel_id: channel_id.to_string(),
+        data: SatelliteMsg::Heartbeat {}.encode(channel_version(storage, channel_id)?)?,
+        timeout: IbcTimeout::from(env.block.time.plus_seconds(config.heartbeat_timeout)),
+    }))
+}
+
+/// Returns the alias of the satellite specified either by alias or by channel
+fn resolve_satellite(
+    storage: &dyn Storage,
+    satellite: String,
+    channel_id: Option<String>,
+) -> Result<String, ContractError> {
+    match channel_id {
+        None if !satellite.is_empty() => Ok(satellite),
+        Some(channel_id) if satellite.is_empty() => SATELLITE_CHANNELS
+            .may_load(storage, &channel_id)?
+            .ok_or(ContractError::SatelliteChannelNotFound { channel_id }),
+        _ => Err(ContractError::InvalidSatelliteTarget {}),
+    }
+}
+
+/// Ensures the optional timeout override is within [`TIMEOUT_LIMITS`]
+fn validate_timeout(timeout: Option<u64>) -> Result<(), ContractError> {
+    match timeout {
+        Some(timeout) if !TIMEOUT_LIMITS.contains(&timeout) => {
+            Err(ContractError::TimeoutLimitsError {})
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Ensures the channel is not used by any other registered satellite
+fn assert_channel_is_free(storage: &dyn Storage, channel_id: &str) -> Result<(), ContractError> {
+    match SATELLITE_CHANNELS.may_load(storage, channel_id)? {
+        Some(alias) => Err(ContractError::ChannelAlreadyRegistered {
+            channel_id: channel_id.to_string(),
+            alias,
+        }),
+        None => Ok(()),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
+        QueryMsg::Config {} => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(to_json_binary(&ConfigResponse {
+                owner: config.owner,
+                timeout: config.timeout,
+                heartbeat_timeout: config.heartbeat_timeout,
+                max_proposal_attempts: config.max_proposal_attempts,
+                retry_error_acks: config.retry_error_acks,
+                heartbeat_interval: config.heartbeat_interval,
+                max_chunk_size: config.max_chunk_size,
+                epoch: config.epoch,
+            })?)
+        }
+        QueryMsg::OwnershipProposal {} => {
+            let proposal = OWNERSHIP_PROPOSAL.may_load(deps.storage)?.map(|proposal| {
+                OwnershipProposalResponse {
+                    owner: proposal.owner,
+                    ttl: proposal.ttl,
+                }
+            });
+            Ok(to_json_binary(&proposal)?)
+        }
         QueryMsg::ProposalState { id } => {
             let state = PROPOSAL_STATE.load(deps.storage, id)?;
             Ok(to_json_binary(&state)?)
         }
-        QueryMsg::LastError {} => Ok(to_json_binary(&LAST_ERROR.load(deps.storage)?)?),
+        QueryMsg::SatelliteHealth {
+            start_after,
+            limit,
+            warning_margin,
+        } => Ok(to_json_binary(&satellites_health(
+            deps.storage,
+            env.block.time,
+            start_after,
+            limit,
+            warning_margin.unwrap_or(DEFAULT_OUTAGE_WARNING_MARGIN),
+        )?)?),
+        QueryMsg::Proposal { id } => Ok(to_json_binary(&load_proposal(deps.storage, id)?)?),
+        QueryMsg::Proposals {
+            start_after,
+            limit,
+            status,
+            channel,
+        } => Ok(to_json_binary(&list_proposals(
+            deps.storage,
+            start_after,
+            limit,
+            status,
+            channel,
+        )?)?),
+        QueryMsg::ProposalCounts {} => Ok(to_json_binary(&proposal_counts(deps.storage)?)?),
+        QueryMsg::ProposalChannels { id } => {
+            Ok(to_json_binary(&proposal_channels(deps.storage, id)?)?)
+        }
+        QueryMsg::LastError {} => Ok(to_json_binary(
+            &LAST_ERROR.may_load(deps.storage)?.unwrap_or_default(),
+        )?),
+        QueryMsg::ProposalError { id } => Ok(to_json_binary(&proposal_errors(deps.storage, id)?)?),
+        QueryMsg::Errors { start_after, limit } => Ok(to_json_binary(&list_errors(
+            deps.storage,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::Satellite { alias } => {
+            Ok(to_json_binary(&SATELLITES.load(deps.storage, &alias)?)?)
+        }
+        QueryMsg::Satellites { start_after, limit } => Ok(to_json_binary(&list_satellites(
+            deps.storage,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::Channels {} => Ok(to_json_binary(&list_channels(deps.storage)?)?),
+        QueryMsg::Reports { channel_id } => {
+            let reports = REPORTS
+                .may_load(deps.storage, &channel_id)?
+                .unwrap_or_else(|| ChannelReports::new(channel_id));
+            Ok(to_json_binary(&reports)?)
+        }
+        QueryMsg::Simulation { id } => Ok(to_json_binary(&SIMULATIONS.load(deps.storage, id)?)?),
+        QueryMsg::Simulations { start_after, limit } => Ok(to_json_binary(&list_simulations(
+            deps.storage,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::AllReports { start_after, limit } => Ok(to_json_binary(&list_reports(
+            deps.storage,
+            start_after,
+            limit,
+        )?)?),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: Empty) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
         "ibc-controller" => match contract_version.version.as_ref() {
-            "1.1.1" => {}
+            "1.1.1" | "1.1.2" => {
+                migrate_config(deps.storage)?;
+                migrate_last_error(deps.storage, &env)?;
+                migrate_proposal_index(deps.storage)?;
+                migrate_channels(deps.branch(), &env)?;
+            }
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -173,16 +990,24 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
 
 #[cfg(test)]
 mod tests {
+    use cosmwasm_std::testing::{mock_env, mock_ibc_channel, mock_info};
     use cosmwasm_std::{from_json, BankMsg, Coin, Uint128};
+    use cosmwasm_std::{Addr, Storage};
+    use ibc_controller_package::{
+        ChannelInfo, ExecutionMode, ProposalError, ProposalResponse, StatusCounts, UpdateConfigMsg,
+    };
 
     use super::*;
-    use crate::test_utils::{init_contract, mock_all, OWNER};
+    use crate::ibc::IBC_ORDERING;
+    use crate::test_utils::{connect_channel, init_contract, mock_all, register_satellite, OWNER};
+    use astroport_ibc::IbcVersion;
 
     #[test]
     fn test_ibc_execute() {
         let (mut deps, env, info) = mock_all(OWNER);
 
         init_contract(&mut deps, env.clone(), info.clone());
+        register_satellite(&mut deps, env.clone(), "neutron", "channel-0");
 
         let channel_id = "channel-0".to_string();
         let proposal_id = 1;
@@ -194,9 +1019,12 @@ mod tests {
             }],
         });
         let msg = ExecuteMsg::IbcExecuteProposal {
-            channel_id,
+            satellite: "neutron".to_string(),
+            channel_id: None,
             proposal_id,
             messages: vec![proposal_msg.clone()],
+            timeout: None,
+            mode: None,
         };
         let resp = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -204,16 +1032,19 @@ mod tests {
         let real_timeout = IbcTimeout::with_timestamp(env.block.time.plus_seconds(360));
         match &resp.messages[0].msg {
             CosmosMsg::Ibc(IbcMsg::SendPacket {
-                channel_id,
+                channel_id: packet_channel,
                 timeout,
                 data,
-            }) if channel_id == channel_id && timeout == &real_timeout => {
+            }) if packet_channel == &channel_id && timeout == &real_timeout => {
                 let msg: SatelliteMsg = from_json(data).unwrap();
                 assert_eq!(
                     msg,
                     SatelliteMsg::ExecuteProposal {
                         id: proposal_id,
-                        messages: vec![proposal_msg]
+                        messages: vec![proposal_msg.clone()],
+                        sent_at: Some(env.block.time),
+                        epoch: Some(0),
+                        mode: ExecutionMode::Atomic,
                     }
                 );
             }
@@ -223,6 +1054,645 @@ mod tests {
         let state = PROPOSAL_STATE
             .load(deps.as_ref().storage, proposal_id)
             .unwrap();
-        assert_eq!(state, ProposalStatus::InProgress {})
+        assert_eq!(state, ProposalStatus::InProgress {});
+
+        let proposal: ProposalResponse = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Proposal { id: proposal_id },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            proposal,
+            ProposalResponse {
+                id: proposal_id,
+                status: ProposalStatus::InProgress,
+                messages: vec![proposal_msg],
+                mode: ExecutionMode::Atomic,
+                dispatches: vec![ProposalDispatch {
+                    channel_id,
+                    status: ProposalStatus::InProgress,
+                    sent_height: env.block.height,
+                    sent_at: env.block.time,
+                    timeout_at: env.block.time.plus_seconds(360),
+                    finished_at: None,
+                    error: None,
+                    attempts: 1,
+                    retryable: false,
+                    timeout: None,
+                    receipt: None,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_satellite_registry() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let execute_proposal = |satellite: &str, proposal_id: u64| ExecuteMsg::IbcExecuteProposal {
+            satellite: satellite.to_string(),
+            channel_id: None,
+            proposal_id,
+            messages: vec![],
+            timeout: None,
+            mode: None,
+        };
+
+        // Unknown satellites are rejected
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal("neutron", 1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SatelliteNotFound {
+                alias: "neutron".to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SendHeartbeat {
+                satellites: vec!["neutron".to_string()],
+                channels: vec![],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SatelliteNotFound {
+                alias: "neutron".to_string()
+            }
+        );
+
+        // Only owner can register satellites
+        let register_msg = ExecuteMsg::RegisterSatellite {
+            alias: "neutron".to_string(),
+            channel_id: "channel-0".to_string(),
+            port_id: "wasm.neutron_satellite".to_string(),
+            connection_id: "connection-0".to_string(),
+            max_signal_outage: *SIGNAL_OUTAGE_LIMITS.start(),
+            timeout: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            register_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            register_msg.clone(),
+        )
+        .unwrap();
+
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), register_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SatelliteAlreadyExists {
+                alias: "neutron".to_string()
+            }
+        );
+
+        // The same channel can't serve two satellites
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::RegisterSatellite {
+                alias: "injective".to_string(),
+                channel_id: "channel-0".to_string(),
+                port_id: "wasm.injective_satellite".to_string(),
+                connection_id: "connection-1".to_string(),
+                max_signal_outage: *SIGNAL_OUTAGE_LIMITS.start(),
+                timeout: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ChannelAlreadyRegistered {
+                channel_id: "channel-0".to_string(),
+                alias: "neutron".to_string()
+            }
+        );
+
+        // Channels the controller never opened are refused
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal("neutron", 1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ChannelNotConnected {
+                channel_id: "channel-0".to_string()
+            }
+        );
+        connect_channel(&mut deps, env.clone(), "channel-0");
+        let channels: Vec<ChannelInfo> =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Channels {}).unwrap()).unwrap();
+        assert_eq!(
+            channels,
+            vec![ChannelInfo {
+                channel_id: "channel-0".to_string(),
+                counterparty_port_id: "their_port".to_string(),
+                counterparty_channel_id: "channel-7".to_string(),
+                connection_id: "connection-2".to_string(),
+                connected_at: env.block.time,
+                version: IbcVersion::LATEST,
+            }]
+        );
+
+        // The satellite must be served by the opened channel
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal("neutron", 1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SatelliteChannelMismatch {
+                alias: "neutron".to_string(),
+                channel_id: "channel-0".to_string()
+            }
+        );
+        let update_ports = |port_id: &str| ExecuteMsg::UpdateSatellite {
+            alias: "neutron".to_string(),
+            channel_id: None,
+            port_id: Some(port_id.to_string()),
+            connection_id: Some("connection-2".to_string()),
+            enabled: None,
+            max_signal_outage: None,
+            timeout: None,
+            clear_timeout: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_ports("wasm.neutron_satellite"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SatelliteChannelMismatch {
+                alias: "neutron".to_string(),
+                channel_id: "channel-0".to_string()
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_ports("their_port"),
+        )
+        .unwrap();
+
+        // Assembly payload refers to the satellite by its channel
+        let assembly_msg = |channel_id: &str, proposal_id: u64| -> ExecuteMsg {
+            from_json(format!(
+                r#"{{"ibc_execute_proposal":{{"channel_id":"{channel_id}","proposal_id":{proposal_id},"messages":[]}}}}"#
+            ))
+            .unwrap()
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            assembly_msg("channel-5", 1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SatelliteChannelNotFound {
+                channel_id: "channel-5".to_string()
+            }
+        );
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            assembly_msg("channel-0", 1),
+        )
+        .unwrap();
+        assert!(resp.attributes.contains(&attr("satellite", "neutron")));
+        assert!(resp.attributes.contains(&attr("channel", "channel-0")));
+
+        // The satellite must be specified either by alias or by channel
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::IbcExecuteProposal {
+                satellite: "neutron".to_string(),
+                channel_id: Some("channel-0".to_string()),
+                proposal_id: 2,
+                messages: vec![],
+                timeout: None,
+                mode: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSatelliteTarget {});
+
+        // Disabled satellites are rejected
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateSatellite {
+                alias: "neutron".to_string(),
+                channel_id: Some("channel-1".to_string()),
+                port_id: None,
+                connection_id: None,
+                enabled: Some(false),
+                max_signal_outage: None,
+                timeout: None,
+                clear_timeout: None,
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal("neutron", 2),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SatelliteDisabled {
+                alias: "neutron".to_string()
+            }
+        );
+
+        let satellite: SatelliteInfo = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Satellite {
+                    alias: "neutron".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(satellite.channel_id, "channel-1");
+        assert!(!satellite.enabled);
+        assert!(!SATELLITE_CHANNELS.has(deps.as_ref().storage, "channel-0"));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::RemoveSatellite {
+                alias: "neutron".to_string(),
+            },
+        )
+        .unwrap();
+        let satellites: Vec<SatelliteInfo> = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::Satellites {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(satellites.is_empty());
+        assert!(!SATELLITE_CHANNELS.has(deps.as_ref().storage, "channel-1"));
+    }
+
+    #[test]
+    fn test_timeout_overrides() {
+        let (mut deps, env, info) = mock_all(OWNER);
+
+        init_contract(&mut deps, env.clone(), info.clone());
+        register_satellite(&mut deps, env.clone(), "neutron", "channel-0");
+        register_satellite(&mut deps, env.clone(), "osmosis", "channel-1");
+
+        let packet_timeouts = |res: &Response| -> Vec<IbcTimeout> {
+            res.messages
+                .iter()
+                .map(|msg| match &msg.msg {
+                    CosmosMsg::Ibc(IbcMsg::SendPacket { timeout, .. }) => timeout.clone(),
+                    _ => panic!("Unexpected message"),
+                })
+                .collect()
+        };
+        let timeout_at = |seconds: u64| IbcTimeout::from(env.block.time.plus_seconds(seconds));
+        let update_satellite =
+            |timeout: Option<u64>, clear_timeout: Option<bool>| ExecuteMsg::UpdateSatellite {
+                alias: "neutron".to_string(),
+                channel_id: None,
+                port_id: None,
+                connection_id: None,
+                enabled: None,
+                max_signal_outage: None,
+                timeout,
+                clear_timeout,
+            };
+
+        // Overrides must be within the timeout limits
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_satellite(Some(*TIMEOUT_LIMITS.end() + 1), None),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TimeoutLimitsError {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_satellite(Some(1800), None),
+        )
+        .unwrap();
+
+        let execute_proposal =
+            |proposal_id: u64, timeout: Option<u64>| ExecuteMsg::IbcExecuteProposalMulti {
+                satellites: vec!["neutron".to_string(), "osmosis".to_string()],
+                proposal_id,
+                messages: vec![],
+                timeout,
+                mode: None,
+            };
+
+        // The satellite override takes precedence over the default timeout
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal(1, None),
+        )
+        .unwrap();
+        assert_eq!(
+            packet_timeouts(&res),
+            vec![timeout_at(1800), timeout_at(360)]
+        );
+
+        // The proposal override takes precedence over both
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal(2, Some(*TIMEOUT_LIMITS.start() - 1)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TimeoutLimitsError {});
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal(2, Some(600)),
+        )
+        .unwrap();
+        assert_eq!(
+            packet_timeouts(&res),
+            vec![timeout_at(600), timeout_at(600)]
+        );
+        let dispatch = PROPOSAL_DISPATCHES
+            .load(deps.as_ref().storage, (2, "channel-0"))
+            .unwrap();
+        assert_eq!(dispatch.timeout, Some(600));
+
+        // Clearing the override restores the default timeout
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_satellite(Some(600), Some(true)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ConflictingTimeoutUpdate {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_satellite(None, Some(true)),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            execute_proposal(3, None),
+        )
+        .unwrap();
+        assert_eq!(
+            packet_timeouts(&res),
+            vec![timeout_at(360), timeout_at(360)]
+        );
+
+        // Heartbeats have their own timeout
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                max_proposal_attempts: None,
+                retry_error_acks: None,
+                heartbeat_interval: None,
+                heartbeat_timeout: Some(120),
+                max_chunk_size: None,
+                epoch: None,
+            }),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::SendHeartbeat {
+                satellites: vec!["neutron".to_string()],
+                channels: vec![],
+            },
+        )
+        .unwrap();
+        assert_eq!(packet_timeouts(&res), vec![timeout_at(120)]);
+    }
+
+    #[test]
+    fn test_config_queries() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let config: ConfigResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(
+            config,
+            ConfigResponse {
+                owner: Addr::unchecked(OWNER),
+                timeout: 360,
+                heartbeat_timeout: 360,
+                max_proposal_attempts: DEFAULT_MAX_PROPOSAL_ATTEMPTS,
+                retry_error_acks: false,
+                heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+                max_chunk_size: 0,
+                epoch: 0,
+            }
+        );
+
+        // The epoch can only move forward
+        let update_epoch = |epoch: u64| {
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                max_proposal_attempts: None,
+                retry_error_acks: None,
+                heartbeat_interval: None,
+                heartbeat_timeout: None,
+                max_chunk_size: None,
+                epoch: Some(epoch),
+            })
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), update_epoch(5)).unwrap();
+        let config: ConfigResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.epoch, 5);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), update_epoch(5)).unwrap_err();
+        assert_eq!(err, ContractError::EpochError { current: 5 });
+
+        let query_proposal = |deps: Deps| -> Option<OwnershipProposalResponse> {
+            from_json(query(deps, mock_env(), QueryMsg::OwnershipProposal {}).unwrap()).unwrap()
+        };
+        assert_eq!(query_proposal(deps.as_ref()), None);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::ProposeNewOwner {
+                owner: "new_owner".to_string(),
+                expires_in: 100,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            query_proposal(deps.as_ref()),
+            Some(OwnershipProposalResponse {
+                owner: Addr::unchecked("new_owner"),
+                ttl: env.block.time.seconds() + 100,
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate() {
+        let (mut deps, env, _) = mock_all(OWNER);
+
+        deps.querier.update_ibc(
+            "my_port",
+            &[mock_ibc_channel(
+                "channel-0",
+                IBC_ORDERING,
+                IbcVersion::V1.as_str(),
+            )],
+        );
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.1.1").unwrap();
+        deps.storage
+            .set(b"config", br#"{"owner":"owner","timeout":360}"#);
+        deps.storage.set(b"last_error", br#""Some error""#);
+        PROPOSAL_STATE
+            .save(deps.as_mut().storage, 1, &ProposalStatus::Failed)
+            .unwrap();
+        PROPOSAL_STATE
+            .save(deps.as_mut().storage, 2, &ProposalStatus::Executed)
+            .unwrap();
+
+        migrate(deps.as_mut(), env.clone(), Empty {}).unwrap();
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.timeout, 360);
+        assert_eq!(config.heartbeat_timeout, 360);
+        assert_eq!(config.max_proposal_attempts, DEFAULT_MAX_PROPOSAL_ATTEMPTS);
+
+        // The proposal the legacy error belongs to is unknown
+        assert!(proposal_errors(deps.as_ref().storage, 1)
+            .unwrap()
+            .is_empty());
+        let errors = proposal_errors(deps.as_ref().storage, 0).unwrap();
+        assert_eq!(
+            errors,
+            vec![ProposalError {
+                proposal_id: 0,
+                channel_id: "".to_string(),
+                attempt: 0,
+                time: env.block.time,
+                error: "Some error".to_string(),
+                code: None,
+                message_index: None,
+            }]
+        );
+        let last_error: String =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::LastError {}).unwrap()).unwrap();
+        assert_eq!(last_error, "Some error");
+
+        let counts = proposal_counts(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            counts.total,
+            StatusCounts {
+                in_progress: 0,
+                executed: 1,
+                failed: 1,
+            }
+        );
+        let proposals = list_proposals(
+            deps.as_ref().storage,
+            None,
+            None,
+            Some(ProposalStatus::Failed),
+            None,
+        )
+        .unwrap();
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].id, 1);
+
+        // Channels opened before the migration are recorded
+        let channel = CHANNELS.load(deps.as_ref().storage, "channel-0").unwrap();
+        assert_eq!(channel.version, IbcVersion::V1);
+
+        // and serve the satellites aliased by the channel ids
+        let resp = execute(
+            deps.as_mut(),
+            env,
+            mock_info(OWNER, &[]),
+            ExecuteMsg::IbcExecuteProposal {
+                satellite: String::new(),
+                channel_id: Some("channel-0".to_string()),
+                proposal_id: 3,
+                messages: vec![],
+                timeout: None,
+                mode: None,
+            },
+        )
+        .unwrap();
+        assert!(resp.attributes.contains(&attr("satellite", "channel-0")));
+        match &resp.messages[0].msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket { channel_id, .. }) => {
+                assert_eq!(channel_id, "channel-0")
+            }
+            _ => panic!("Unexpected message"),
+        }
     }
 }
@@ -1,8 +1,14 @@
-use astroport_ibc::TIMEOUT_LIMITS;
-use cosmwasm_std::StdError;
+use astroport_ibc::{SIGNAL_OUTAGE_LIMITS, TIMEOUT_LIMITS};
+use cosmwasm_std::{StdError, Timestamp};
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+use crate::state::{MAX_HEARTBEAT_INTERVAL, MIN_CHUNK_SIZE, PROPOSAL_ATTEMPTS_LIMITS};
+
+/// Never is a placeholder to ensure we don't return any errors
+#[derive(Error, Debug)]
+pub enum Never {}
+
+#[derive(Debug, Error, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
@@ -22,4 +28,87 @@ pub enum ContractError {
         TIMEOUT_LIMITS.end()
     )]
     TimeoutLimitsError {},
+
+    #[error(
+        "Signal outage must be within limits ({0} <= outage <= {1})",
+        SIGNAL_OUTAGE_LIMITS.start(),
+        SIGNAL_OUTAGE_LIMITS.end()
+    )]
+    SignalOutageLimitsError {},
+
+    #[error(
+        "Max proposal attempts must be within limits ({0} <= attempts <= {1})",
+        PROPOSAL_ATTEMPTS_LIMITS.start(),
+        PROPOSAL_ATTEMPTS_LIMITS.end()
+    )]
+    ProposalAttemptsLimitsError {},
+
+    #[error(
+        "Heartbeat interval must not exceed {0} seconds",
+        MAX_HEARTBEAT_INTERVAL
+    )]
+    HeartbeatIntervalError {},
+
+    #[error("Chunk size must be either 0 or at least {0} bytes", MIN_CHUNK_SIZE)]
+    ChunkSizeError {},
+
+    #[error("Epoch must be greater than the current epoch {current}")]
+    EpochError { current: u64 },
+
+    #[error("Heartbeat was already sent to channel {channel_id} recently")]
+    HeartbeatTooEarly { channel_id: String },
+
+    #[error("Simulation was already sent to channel {channel_id} recently. Next one can be sent at {next_simulation}")]
+    SimulationTooEarly {
+        channel_id: String,
+        next_simulation: Timestamp,
+    },
+
+    #[error("No satellites are due for a heartbeat")]
+    NoHeartbeatsDue {},
+
+    #[error("Proposal {id} can't be retried on channel {channel}")]
+    ProposalNotRetryable { id: u64, channel: String },
+
+    #[error("Proposal {id} was not sent to channel {channel}")]
+    ProposalDispatchNotFound { id: u64, channel: String },
+
+    #[error("Satellite {alias} is not registered")]
+    SatelliteNotFound { alias: String },
+
+    #[error("No satellite is registered for channel {channel_id}")]
+    SatelliteChannelNotFound { channel_id: String },
+
+    #[error("Either the satellite or the channel must be specified")]
+    InvalidSatelliteTarget {},
+
+    #[error("Satellite {alias} is disabled")]
+    SatelliteDisabled { alias: String },
+
+    #[error("Satellite {alias} is already registered")]
+    SatelliteAlreadyExists { alias: String },
+
+    #[error("Channel {channel_id} is already used by satellite {alias}")]
+    ChannelAlreadyRegistered { channel_id: String, alias: String },
+
+    #[error("Channel {channel_id} was not opened by the controller")]
+    ChannelNotConnected { channel_id: String },
+
+    #[error("Port or connection of satellite {alias} doesn't match channel {channel_id}")]
+    SatelliteChannelMismatch { alias: String, channel_id: String },
+
+    #[error("Satellite {alias} is specified more than once")]
+    DuplicateSatellite { alias: String },
+
+    #[error("At least one satellite must be specified")]
+    NoSatellites {},
+
+    #[error("Batch must contain at least one proposal")]
+    EmptyBatch {},
+
+    #[error("Satellite alias must not be empty")]
+    InvalidSatelliteAlias {},
+
+    #[error("Timeout can't be set and cleared at the same time")]
+    ConflictingTimeoutUpdate {},
 }
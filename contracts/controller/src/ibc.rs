@@ -1,18 +1,28 @@
+use std::fmt::Display;
+
 use cosmwasm_std::{
-    entry_point, from_json, wasm_execute, Addr, DepsMut, Env, Ibc3ChannelOpenResponse,
-    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
-    IbcChannelOpenResponse, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
-    IbcReceiveResponse, StdError, StdResult, SubMsg,
+    entry_point, from_json, to_json_binary, wasm_execute, Addr, Binary, DepsMut, Env,
+    Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcChannelOpenResponse, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcReceiveResponse, StdError, StdResult, Storage, SubMsg, Timestamp,
 };
 
-use astro_satellite_package::{IbcAckResult, SatelliteMsg};
+use astro_satellite_package::{
+    AckError, AckErrorCode, BatchProposalResult, ExecutionReceipt, IbcAckResult, IbcVersion,
+    QueueStatus, SatelliteMsg, SatelliteReport, SimulationResult, CHUNK_ACK, QUEUED_ACK,
+};
 use ibc_controller_package::astroport_governance::assembly::{
     ExecuteMsg as AssemblyExecuteMsg, ProposalStatus,
 };
 
-use crate::state::{CONFIG, LAST_ERROR, PROPOSAL_STATE};
+use ibc_controller_package::{ChannelInfo, SimulationStatus};
+
+use crate::error::Never;
+use crate::state::{
+    channel_version, process_proposal_outcome, record_packet_outcome, save_report, PacketOutcome,
+    CHANNELS, CONFIG, PROPOSAL_DISPATCHES, SATELLITE_CHANNELS, SIMULATIONS,
+};
 
-pub const IBC_APP_VERSION: &str = "astroport-ibc-v1";
 pub const IBC_ORDERING: IbcOrder = IbcOrder::Unordered;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -28,56 +38,187 @@ pub fn ibc_channel_open(
             "Ordering is invalid. The channel must be unordered",
         ));
     }
-    if channel.version != IBC_APP_VERSION {
-        return Err(StdError::generic_err(format!(
-            "Must set version to `{IBC_APP_VERSION}`",
-        )));
-    }
 
-    if let Some(counter_version) = msg.counterparty_version() {
-        if counter_version != IBC_APP_VERSION {
-            return Err(StdError::generic_err(format!(
-                "Counterparty version must be `{IBC_APP_VERSION}`"
-            )));
-        }
-    }
+    let version = match msg.counterparty_version() {
+        // Agree on the highest version supported by both sides
+        Some(counter_version) => IbcVersion::negotiate(counter_version).ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Counterparty version `{counter_version}` is not supported"
+            ))
+        })?,
+        // Propose the latest version unless the relayer requested a specific one
+        None if channel.version.is_empty() => IbcVersion::LATEST,
+        None => IbcVersion::parse(&channel.version).ok_or_else(|| {
+            StdError::generic_err(format!("Version `{}` is not supported", channel.version))
+        })?,
+    };
 
     Ok(Some(Ibc3ChannelOpenResponse {
-        version: IBC_APP_VERSION.to_string(),
+        version: version.to_string(),
     }))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_channel_connect(
-    _deps: DepsMut,
-    _env: Env,
+    deps: DepsMut,
+    env: Env,
     msg: IbcChannelConnectMsg,
 ) -> StdResult<IbcBasicResponse> {
     let channel = msg.channel();
 
-    if let Some(counter_version) = msg.counterparty_version() {
-        if counter_version != IBC_APP_VERSION {
-            return Err(StdError::generic_err(format!(
-                "Counterparty version must be `{IBC_APP_VERSION}`"
-            )));
-        }
-    }
+    // The version chosen by the counterparty on OpenTry is final
+    let version = msg.counterparty_version().unwrap_or(&channel.version);
+    let version = IbcVersion::parse(version)
+        .ok_or_else(|| StdError::generic_err(format!("Version `{version}` is not supported")))?;
+
+    CHANNELS.save(
+        deps.storage,
+        &channel.endpoint.channel_id,
+        &ChannelInfo {
+            channel_id: channel.endpoint.channel_id.clone(),
+            counterparty_port_id: channel.counterparty_endpoint.port_id.clone(),
+            counterparty_channel_id: channel.counterparty_endpoint.channel_id.clone(),
+            connection_id: channel.connection_id.clone(),
+            connected_at: env.block.time,
+            version,
+        },
+    )?;
 
     Ok(IbcBasicResponse::new()
         .add_attribute("action", "ibc_connect")
-        .add_attribute("channel_id", &channel.endpoint.channel_id))
+        .add_attribute("channel_id", &channel.endpoint.channel_id)
+        .add_attribute("version", version.as_str()))
+}
+
+/// Create a serialized success message
+pub fn ack_ok() -> Binary {
+    to_json_binary(&IbcAckResult::Ok(b"ok".into())).unwrap()
+}
+
+/// Create a serialized error message
+pub fn ack_fail(err: impl Display) -> Binary {
+    to_json_binary(&IbcAckResult::Error(err.to_string())).unwrap()
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+/// We should not return an error if possible, but rather an acknowledgement of failure
 pub fn ibc_packet_receive(
-    _deps: DepsMut,
-    _env: Env,
-    _msg: IbcPacketReceiveMsg,
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, Never> {
+    do_packet_receive(deps, env, msg).or_else(|err| {
+        Ok(IbcReceiveResponse::new()
+            .add_attribute("action", "ibc_packet_receive")
+            .set_ack(ack_fail(err)))
+    })
+}
+
+fn do_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
 ) -> StdResult<IbcReceiveResponse> {
-    unimplemented!()
+    let channel_id = &msg.packet.dest.channel_id;
+    let version = channel_version(deps.storage, channel_id)?;
+    if version < IbcVersion::V2 {
+        return Err(StdError::generic_err(format!(
+            "Reports are not supported by {version}"
+        )));
+    }
+    // Channels not serving a registered satellite can't report
+    if !SATELLITE_CHANNELS.has(deps.storage, channel_id) {
+        return Err(StdError::generic_err(format!(
+            "No satellite is registered for channel {channel_id}"
+        )));
+    }
+
+    let mut response = IbcReceiveResponse::new()
+        .add_attribute("action", "ibc_packet_receive")
+        .add_attribute("channel_id", channel_id)
+        .set_ack(ack_ok());
+
+    let report: SatelliteReport = from_json(&msg.packet.data)?;
+    let kind = match &report {
+        SatelliteReport::Status(_) => "status",
+        SatelliteReport::Balances(_) => "balances",
+        SatelliteReport::Config(_) => "config",
+        SatelliteReport::ProposalOutcome(_) => "proposal_outcome",
+    };
+
+    if let SatelliteReport::ProposalOutcome(outcome) = report {
+        // The proposal was queued by the satellite timelock and is final now
+        let packet_outcome = match outcome.status {
+            QueueStatus::Executed => PacketOutcome::Executed(None),
+            QueueStatus::Failed { error } => PacketOutcome::Failed(AckError::decode(&error)),
+            QueueStatus::Vetoed => {
+                PacketOutcome::Failed(AckError::new(AckErrorCode::Unknown, "Proposal was vetoed"))
+            }
+            QueueStatus::Queued => {
+                return Err(StdError::generic_err(format!(
+                    "Proposal {} is not final yet",
+                    outcome.id
+                )))
+            }
+        };
+
+        let confirm_msg = finish_proposal(
+            deps.storage,
+            outcome.id,
+            channel_id,
+            packet_outcome,
+            env.block.time,
+        )?;
+        response = response.add_submessages(confirm_msg);
+
+        response = response.add_attribute("proposal_id", outcome.id.to_string());
+    } else {
+        save_report(deps.storage, channel_id, report)?;
+    }
+
+    Ok(response.add_attribute("report", kind))
+}
+
+/// Records the proposal outcome on the channel.
+/// Returns the Assembly callback once the proposal is complete
+fn finish_proposal(
+    storage: &mut dyn Storage,
+    id: u64,
+    channel_id: &str,
+    outcome: PacketOutcome,
+    time: Timestamp,
+) -> StdResult<Option<SubMsg>> {
+    process_proposal_outcome(storage, id, channel_id, outcome, time)?
+        .map(|new_status| {
+            let config = CONFIG.load(storage)?;
+            confirm_assembly(&config.owner, id, new_status)
+        })
+        .transpose()
+}
+
+/// Decodes the execution receipt from the successful acknowledgement.
+/// v1 satellites acknowledge proposals with a plain `ok`
+fn parse_receipt(data: &Binary) -> Option<ExecutionReceipt> {
+    from_json(data).ok()
+}
+
+/// Whether the dispatch attempt still awaits its outcome on the channel.
+/// Chunks of the proposal may be acknowledged after the proposal has finished
+/// or after it was retried, such outcomes are ignored
+fn dispatch_in_progress(
+    storage: &dyn Storage,
+    id: u64,
+    channel_id: &str,
+    attempt: u32,
+) -> StdResult<bool> {
+    Ok(PROPOSAL_DISPATCHES
+        .may_load(storage, (id, channel_id))?
+        .is_some_and(|dispatch| {
+            dispatch.status == ProposalStatus::InProgress && dispatch.attempts == attempt
+        }))
 }
 
-fn confirm_assembly(
+pub(crate) fn confirm_assembly(
     assembly: &Addr,
     proposal_id: u64,
     status: ProposalStatus,
@@ -95,44 +236,90 @@ fn confirm_assembly(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_timeout(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcPacketTimeoutMsg,
 ) -> StdResult<IbcBasicResponse> {
     let mut res = IbcBasicResponse::new();
 
-    let satellite_msg: SatelliteMsg = from_json(&msg.packet.data)?;
+    let channel_id = &msg.packet.src.channel_id;
+    record_packet_outcome(
+        deps.storage,
+        channel_id,
+        &PacketOutcome::TimedOut,
+        env.block.time,
+    )?;
+
+    let version = channel_version(deps.storage, channel_id)?;
+    let satellite_msg = SatelliteMsg::decode(&msg.packet.data, version)?;
     match satellite_msg {
         SatelliteMsg::ExecuteProposal { id, .. } => {
             // The original packet was a proposal
-            let new_status = PROPOSAL_STATE.update(deps.storage, id, |state| match state {
-                None => Err(StdError::generic_err(format!(
-                    "Proposal {} was not executed via controller",
-                    id
-                ))),
-                Some(state) => {
-                    if state == ProposalStatus::InProgress {
-                        Ok(ProposalStatus::Failed {})
-                    } else {
-                        Err(StdError::generic_err(format!(
-                            "Proposal id: {} state is already {}",
-                            id, state
-                        )))
-                    }
-                }
-            })?;
-            let config = CONFIG.load(deps.storage)?;
+            let confirm_msg = finish_proposal(
+                deps.storage,
+                id,
+                channel_id,
+                PacketOutcome::TimedOut,
+                env.block.time,
+            )?;
 
             res = res
-                .add_submessage(confirm_assembly(&config.owner, id, new_status)?)
+                .add_submessages(confirm_msg)
                 .add_attribute("action", "proposal_timeout")
-                .add_attribute("proposal_id", id.to_string());
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("channel_id", channel_id);
+        }
+        SatelliteMsg::ExecuteChunk {
+            id, index, attempt, ..
+        } => {
+            // A single lost chunk fails the whole proposal
+            if dispatch_in_progress(deps.storage, id, channel_id, attempt)? {
+                let confirm_msg = finish_proposal(
+                    deps.storage,
+                    id,
+                    channel_id,
+                    PacketOutcome::TimedOut,
+                    env.block.time,
+                )?;
+                res = res.add_submessages(confirm_msg);
+            }
+
+            res = res
+                .add_attribute("action", "chunk_timeout")
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("chunk", index.to_string())
+                .add_attribute("channel_id", channel_id);
+        }
+        SatelliteMsg::SimulateProposal { id, .. } => {
+            finish_simulation(deps.storage, id, SimulationStatus::TimedOut, env.block.time)?;
+            res = res
+                .add_attribute("action", "simulation_timeout")
+                .add_attribute("simulation_id", id.to_string())
+                .add_attribute("channel_id", channel_id);
+        }
+        SatelliteMsg::ExecuteBatch { proposals, .. } => {
+            // Every proposal of the batch timed out
+            res = res
+                .add_attribute("action", "batch_timeout")
+                .add_attribute("channel_id", channel_id);
+            for proposal in proposals {
+                let confirm_msg = finish_proposal(
+                    deps.storage,
+                    proposal.id,
+                    channel_id,
+                    PacketOutcome::TimedOut,
+                    env.block.time,
+                )?;
+                res = res
+                    .add_submessages(confirm_msg)
+                    .add_attribute("proposal_id", proposal.id.to_string());
+            }
         }
         SatelliteMsg::Heartbeat {} => {
             // The original packet was a heartbeat
-            // We don't do anything with the timeout for a heartbeat
+            // Only the satellite liveness data is updated
             res = res
                 .add_attribute("action", "heartbeat_timeout")
-                .add_attribute("channel_id", msg.packet.src.channel_id)
+                .add_attribute("channel_id", channel_id)
         }
     }
     Ok(res)
@@ -141,58 +328,155 @@ pub fn ibc_packet_timeout(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_ack(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcPacketAckMsg,
 ) -> StdResult<IbcBasicResponse> {
     let mut res = IbcBasicResponse::new();
 
     let ibc_ack: IbcAckResult = from_json(&msg.acknowledgement.data)?;
-    let satellite_msg: SatelliteMsg = from_json(&msg.original_packet.data)?;
+    let queued = matches!(&ibc_ack, IbcAckResult::Ok(data) if data.as_slice() == QUEUED_ACK);
+    let outcome = match ibc_ack.clone() {
+        IbcAckResult::Ok(data) => PacketOutcome::Executed(parse_receipt(&data)),
+        IbcAckResult::Error(err) => PacketOutcome::Failed(AckError::decode(&err)),
+    };
+    let channel_id = &msg.original_packet.src.channel_id;
+    record_packet_outcome(deps.storage, channel_id, &outcome, env.block.time)?;
+
+    let version = channel_version(deps.storage, channel_id)?;
+    let satellite_msg = SatelliteMsg::decode(&msg.original_packet.data, version)?;
     match satellite_msg {
+        SatelliteMsg::ExecuteProposal { id, .. } if queued => {
+            // The satellite timelock delays the execution.
+            // The proposal stays in progress until the satellite reports the outcome
+            res = res
+                .add_attribute("action", "proposal_queued")
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("channel_id", channel_id);
+        }
         SatelliteMsg::ExecuteProposal { id, .. } => {
             // The original packet was a proposal
-            let mut err_msg = "".to_string();
-            let new_status = PROPOSAL_STATE.update(deps.storage, id, |state| match state {
-                None => Err(StdError::generic_err(format!(
-                    "Proposal {} was not executed via controller",
-                    id
-                ))),
-                Some(state) => {
-                    if state == ProposalStatus::InProgress {
-                        match ibc_ack {
-                            IbcAckResult::Ok(_) => Ok(ProposalStatus::Executed {}),
-                            IbcAckResult::Error(err) => {
-                                err_msg = err;
-                                Ok(ProposalStatus::Failed {})
-                            }
-                        }
-                    } else {
-                        Err(StdError::generic_err(format!(
-                            "Proposal id: {} state is already {}",
-                            id, state
-                        )))
-                    }
-                }
-            })?;
-            LAST_ERROR.save(deps.storage, &err_msg)?;
-            let config = CONFIG.load(deps.storage)?;
+            let confirm_msg =
+                finish_proposal(deps.storage, id, channel_id, outcome, env.block.time)?;
 
             res = res
-                .add_submessage(confirm_assembly(&config.owner, id, new_status)?)
+                .add_submessages(confirm_msg)
                 .add_attribute("action", "proposal_ack")
-                .add_attribute("proposal_id", id.to_string());
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("channel_id", channel_id);
         }
         SatelliteMsg::Heartbeat {} => {
             // The original packet was a heartbeat
-            // We don't do anything with the ack from a heartbeat
+            // Only the satellite liveness data is updated
             res = res
                 .add_attribute("action", "heartbeat_ack")
-                .add_attribute("channel_id", msg.original_packet.src.channel_id)
+                .add_attribute("channel_id", channel_id)
+        }
+        SatelliteMsg::ExecuteChunk {
+            id, index, attempt, ..
+        } => {
+            // Only the chunk which completed the upload carries the proposal result
+            let pending =
+                matches!(&ibc_ack, IbcAckResult::Ok(data) if data.as_slice() == CHUNK_ACK);
+            if !pending && !queued && dispatch_in_progress(deps.storage, id, channel_id, attempt)? {
+                let confirm_msg =
+                    finish_proposal(deps.storage, id, channel_id, outcome, env.block.time)?;
+                res = res.add_submessages(confirm_msg);
+            }
+
+            res = res
+                .add_attribute("action", "chunk_ack")
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("chunk", index.to_string())
+                .add_attribute("channel_id", channel_id);
+        }
+        SatelliteMsg::SimulateProposal { id, .. } => {
+            let status = match ibc_ack {
+                IbcAckResult::Ok(data) => match from_json(data) {
+                    Ok(SimulationResult::Passed {}) => SimulationStatus::Passed,
+                    Ok(SimulationResult::Failed { error }) => SimulationStatus::Failed { error },
+                    Err(err) => SimulationStatus::Failed {
+                        error: format!("Invalid simulation result: {err}"),
+                    },
+                },
+                IbcAckResult::Error(error) => SimulationStatus::Failed {
+                    error: AckError::decode(&error).error,
+                },
+            };
+            res = res
+                .add_attribute("action", "simulation_ack")
+                .add_attribute("simulation_id", id.to_string())
+                .add_attribute("channel_id", channel_id)
+                .add_attribute(
+                    "passed",
+                    matches!(status, SimulationStatus::Passed).to_string(),
+                );
+            finish_simulation(deps.storage, id, status, env.block.time)?;
+        }
+        SatelliteMsg::ExecuteBatch { proposals, .. } => {
+            // The satellite reports the result of every proposal unless the whole packet failed
+            let results: Vec<BatchProposalResult> = match &ibc_ack {
+                IbcAckResult::Ok(data) => from_json(data)?,
+                IbcAckResult::Error(_) => vec![],
+            };
+
+            res = res
+                .add_attribute("action", "batch_ack")
+                .add_attribute("channel_id", channel_id);
+            for proposal in proposals {
+                let result = match &ibc_ack {
+                    IbcAckResult::Ok(_) => results
+                        .iter()
+                        .find(|result| result.id == proposal.id)
+                        .map(|result| result.result.clone())
+                        .unwrap_or_else(|| {
+                            IbcAckResult::Error("Batch result is missing".to_string())
+                        }),
+                    err => err.clone(),
+                };
+                let outcome = match result {
+                    IbcAckResult::Ok(data) if data.as_slice() == QUEUED_ACK => {
+                        // The proposal stays in progress until the satellite reports the outcome
+                        res = res.add_attribute("queued_proposal_id", proposal.id.to_string());
+                        continue;
+                    }
+                    IbcAckResult::Ok(data) => PacketOutcome::Executed(parse_receipt(&data)),
+                    IbcAckResult::Error(err) => PacketOutcome::Failed(AckError::decode(&err)),
+                };
+
+                let confirm_msg = finish_proposal(
+                    deps.storage,
+                    proposal.id,
+                    channel_id,
+                    outcome,
+                    env.block.time,
+                )?;
+                res = res
+                    .add_submessages(confirm_msg)
+                    .add_attribute("proposal_id", proposal.id.to_string());
+            }
         }
     }
     Ok(res)
 }
 
+/// Saves the simulation result received from the satellite
+fn finish_simulation(
+    storage: &mut dyn Storage,
+    id: u64,
+    status: SimulationStatus,
+    time: Timestamp,
+) -> StdResult<()> {
+    SIMULATIONS.update::<_, StdError>(storage, id, |simulation| {
+        let mut simulation =
+            simulation.ok_or_else(|| StdError::not_found(format!("Simulation {id}")))?;
+        simulation.status = status;
+        simulation.finished_at = Some(time);
+        Ok(simulation)
+    })?;
+
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_channel_close(
     _deps: DepsMut,
@@ -205,28 +489,54 @@ pub fn ibc_channel_close(
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::testing::{
-        mock_ibc_channel_close_init, mock_ibc_packet_ack, mock_ibc_packet_timeout,
+        mock_env, mock_ibc_channel_close_init, mock_ibc_channel_connect_ack,
+        mock_ibc_channel_open_init, mock_ibc_channel_open_try, mock_ibc_packet_ack,
+        mock_ibc_packet_recv, mock_ibc_packet_timeout,
+    };
+    use cosmwasm_std::{
+        attr, coins, to_json_binary, Binary, CosmosMsg, Deps, IbcAcknowledgement, IbcMsg, Response,
+        WasmMsg,
+    };
+
+    use astro_satellite_package::{
+        BalanceReport, BatchProposal, BatchProposalResult, ExecutionMode, MessageResult,
+        ProposalOutcomeReport, StatusReport,
     };
-    use cosmwasm_std::{attr, to_json_binary, Binary, CosmosMsg, IbcAcknowledgement, WasmMsg};
 
-    use ibc_controller_package::ExecuteMsg;
+    use cosmwasm_std::testing::mock_info;
+    use ibc_controller_package::{
+        ChannelHealth, ChannelProposalStatus, ChannelReports, ChannelStatusCounts, ExecuteMsg,
+        ProposalCountsResponse, ProposalError, ProposalResponse, QueryMsg, SatelliteHealthResponse,
+        SimulationInfo, StatusCounts, UpdateConfigMsg,
+    };
 
-    use crate::contract::execute;
-    use crate::test_utils::{init_contract, mock_all, OWNER};
+    use crate::contract::{execute, query};
+    use crate::error::ContractError;
+    use crate::state::{
+        save_proposal_status, DEFAULT_OUTAGE_WARNING_MARGIN, MAX_HEARTBEAT_INTERVAL,
+        PROPOSAL_DISPATCHES, PROPOSAL_STATE, REPORTS, SATELLITE_HEALTH, SIMULATION_INTERVAL,
+    };
+    use crate::test_utils::{connect_channel, init_contract, mock_all, register_satellite, OWNER};
 
     use super::*;
 
-    fn mock_ibc_execute_proposal(channel_id: &str, proposal_id: u64) -> ExecuteMsg {
+    const SATELLITE: &str = "neutron";
+
+    fn mock_ibc_execute_proposal(proposal_id: u64) -> ExecuteMsg {
         ExecuteMsg::IbcExecuteProposal {
-            channel_id: channel_id.to_string(),
+            satellite: SATELLITE.to_string(),
+            channel_id: None,
             proposal_id,
             messages: vec![],
+            timeout: None,
+            mode: None,
         }
     }
 
-    fn mock_ibc_heartbeat(channel_id: &str) -> ExecuteMsg {
+    fn mock_ibc_heartbeat() -> ExecuteMsg {
         ExecuteMsg::SendHeartbeat {
-            channels: vec![channel_id.to_string()],
+            satellites: vec![SATELLITE.to_string()],
+            channels: vec![],
         }
     }
 
@@ -236,9 +546,10 @@ mod tests {
         init_contract(&mut deps, env.clone(), info.clone());
 
         let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
         let mut proposal_id = 1;
 
-        let msg = mock_ibc_execute_proposal(channel_id, proposal_id);
+        let msg = mock_ibc_execute_proposal(proposal_id);
         execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Ok acknowledgment
@@ -247,6 +558,9 @@ mod tests {
             &SatelliteMsg::ExecuteProposal {
                 id: proposal_id,
                 messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
             },
             IbcAcknowledgement::encode_json(&IbcAckResult::Ok(Binary::default())).unwrap(),
         )
@@ -277,13 +591,16 @@ mod tests {
 
         // Failed proposal
         proposal_id += 1;
-        let msg = mock_ibc_execute_proposal(channel_id, proposal_id);
+        let msg = mock_ibc_execute_proposal(proposal_id);
         execute(deps.as_mut(), env.clone(), info, msg).unwrap();
         let ack_msg = mock_ibc_packet_ack(
             channel_id,
             &SatelliteMsg::ExecuteProposal {
                 id: proposal_id,
                 messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
             },
             IbcAcknowledgement::encode_json(&IbcAckResult::Error("Some error".to_string()))
                 .unwrap(),
@@ -319,12 +636,19 @@ mod tests {
             .unwrap();
         assert_eq!(state, ProposalStatus::Executed);
 
+        let last_error: String =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::LastError {}).unwrap()).unwrap();
+        assert_eq!(last_error, "Some error");
+
         // Proposal with unknown id
         let ack_msg = mock_ibc_packet_ack(
             channel_id,
             &SatelliteMsg::ExecuteProposal {
                 id: 128,
                 messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
             },
             IbcAcknowledgement::encode_json(&IbcAckResult::Error("Some error".to_string()))
                 .unwrap(),
@@ -338,124 +662,1751 @@ mod tests {
     }
 
     #[test]
-    fn channel_heartbeat_ack() {
+    fn multi_channel_proposal() {
         let (mut deps, env, info) = mock_all(OWNER);
         init_contract(&mut deps, env.clone(), info.clone());
+        register_satellite(&mut deps, env.clone(), SATELLITE, "channel-0");
+        register_satellite(&mut deps, env.clone(), "injective", "channel-1");
 
-        let channel_id = "channel-0";
+        let proposal_id = 1;
+        let msg = ExecuteMsg::IbcExecuteProposalMulti {
+            satellites: vec![SATELLITE.to_string(), "injective".to_string()],
+            proposal_id,
+            messages: vec![],
+            timeout: None,
+            mode: None,
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(resp.messages.len(), 2);
 
-        let msg = mock_ibc_heartbeat(channel_id);
-        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // The same proposal can't be sent twice
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            mock_ibc_execute_proposal(proposal_id),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ProposalAlreadyExists { proposal_id });
 
-        // Ok acknowledgment
+        let packet = SatelliteMsg::ExecuteProposal {
+            id: proposal_id,
+            messages: vec![],
+            sent_at: None,
+            epoch: None,
+            mode: ExecutionMode::Atomic,
+        };
+
+        // The first satellite executed the proposal, however, Assembly is not notified yet
         let ack_msg = mock_ibc_packet_ack(
-            channel_id,
-            &SatelliteMsg::Heartbeat {},
+            "channel-0",
+            &packet,
             IbcAcknowledgement::encode_json(&IbcAckResult::Ok(Binary::default())).unwrap(),
         )
         .unwrap();
-        let resp = ibc_packet_ack(deps.as_mut(), env, ack_msg).unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+        assert!(resp.messages.is_empty());
+        assert_eq!(
+            PROPOSAL_STATE
+                .load(deps.as_ref().storage, proposal_id)
+                .unwrap(),
+            ProposalStatus::InProgress
+        );
 
-        assert!(resp
-            .attributes
-            .contains(&attr("action", "heartbeat_ack".to_string())));
-        assert!(resp.attributes.contains(&attr("channel_id", channel_id)));
+        // The second one timed out which completes the proposal
+        let timeout_msg = mock_ibc_packet_timeout("channel-1", &packet).unwrap();
+        let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap();
+        let valid_msg = to_json_binary(&AssemblyExecuteMsg::IBCProposalCompleted {
+            proposal_id,
+            status: ProposalStatus::Failed,
+        })
+        .unwrap();
+        assert!(matches!(
+            &resp.messages[..],
+            [SubMsg {
+                msg: CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }),
+                ..
+            }] if contract_addr == OWNER && msg == &valid_msg
+        ));
+        assert_eq!(
+            PROPOSAL_STATE
+                .load(deps.as_ref().storage, proposal_id)
+                .unwrap(),
+            ProposalStatus::Failed
+        );
+
+        let channels: Vec<ChannelProposalStatus> = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::ProposalChannels { id: proposal_id },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            channels,
+            vec![
+                ChannelProposalStatus {
+                    channel_id: "channel-0".to_string(),
+                    status: ProposalStatus::Executed
+                },
+                ChannelProposalStatus {
+                    channel_id: "channel-1".to_string(),
+                    status: ProposalStatus::Failed
+                }
+            ]
+        );
+        let dispatch = PROPOSAL_DISPATCHES
+            .load(deps.as_ref().storage, (proposal_id, "channel-1"))
+            .unwrap();
+        assert_eq!(dispatch.error.as_deref(), Some("Packet timed out"));
     }
 
     #[test]
-    fn channel_proposal_timeout() {
+    fn proposal_queries() {
         let (mut deps, env, info) = mock_all(OWNER);
         init_contract(&mut deps, env.clone(), info.clone());
+        register_satellite(&mut deps, env.clone(), SATELLITE, "channel-0");
+        register_satellite(&mut deps, env.clone(), "injective", "channel-1");
 
-        let channel_id = "channel-0";
-        let proposal_id = 1;
-
-        let msg = mock_ibc_execute_proposal(channel_id, proposal_id);
-        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        for proposal_id in 1..=3 {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                mock_ibc_execute_proposal(proposal_id),
+            )
+            .unwrap();
+        }
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::IbcExecuteProposal {
+                satellite: "injective".to_string(),
+                channel_id: None,
+                proposal_id: 4,
+                messages: vec![],
+                timeout: None,
+                mode: None,
+            },
+        )
+        .unwrap();
 
-        let timeout_msg = mock_ibc_packet_timeout(
-            channel_id,
+        let ack_msg = mock_ibc_packet_ack(
+            "channel-0",
             &SatelliteMsg::ExecuteProposal {
-                id: proposal_id,
+                id: 2,
                 messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
             },
+            IbcAcknowledgement::encode_json(&IbcAckResult::Ok(Binary::default())).unwrap(),
         )
         .unwrap();
-        let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg.clone()).unwrap();
-        assert!(resp
-            .attributes
-            .contains(&attr("proposal_id", proposal_id.to_string())));
+        ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
 
-        let state = PROPOSAL_STATE
-            .load(deps.as_ref().storage, proposal_id)
-            .unwrap();
-        assert_eq!(state, ProposalStatus::Failed);
+        let query_ids = |msg: QueryMsg| -> Vec<u64> {
+            from_json::<Vec<ProposalResponse>>(query(deps.as_ref(), env.clone(), msg).unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|proposal| proposal.id)
+                .collect()
+        };
 
-        assert_eq!(resp.messages.len(), 1);
-        let valid_msg = to_json_binary(&AssemblyExecuteMsg::IBCProposalCompleted {
-            proposal_id,
-            status: ProposalStatus::Failed,
-        })
+        assert_eq!(
+            query_ids(QueryMsg::Proposals {
+                start_after: Some(1),
+                limit: Some(2),
+                status: None,
+                channel: None,
+            }),
+            vec![2, 3]
+        );
+        assert_eq!(
+            query_ids(QueryMsg::Proposals {
+                start_after: None,
+                limit: None,
+                status: Some(ProposalStatus::InProgress),
+                channel: Some("channel-0".to_string()),
+            }),
+            vec![1, 3]
+        );
+        assert_eq!(
+            query_ids(QueryMsg::Proposals {
+                start_after: None,
+                limit: None,
+                status: Some(ProposalStatus::Executed),
+                channel: None,
+            }),
+            vec![2]
+        );
+
+        let counts: ProposalCountsResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::ProposalCounts {}).unwrap()).unwrap();
+        assert_eq!(
+            counts,
+            ProposalCountsResponse {
+                total: StatusCounts {
+                    in_progress: 3,
+                    executed: 1,
+                    failed: 0,
+                },
+                channels: vec![
+                    ChannelStatusCounts {
+                        channel_id: "channel-0".to_string(),
+                        counts: StatusCounts {
+                            in_progress: 2,
+                            executed: 1,
+                            failed: 0,
+                        },
+                    },
+                    ChannelStatusCounts {
+                        channel_id: "channel-1".to_string(),
+                        counts: StatusCounts {
+                            in_progress: 1,
+                            executed: 0,
+                            failed: 0,
+                        },
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn retry_proposal() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                max_proposal_attempts: Some(2),
+                retry_error_acks: None,
+                heartbeat_interval: None,
+                heartbeat_timeout: None,
+                max_chunk_size: None,
+                epoch: None,
+            }),
+        )
         .unwrap();
-        assert!(matches!(
-            &resp.messages[0],
-            SubMsg {
-                msg: CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }),
-                ..
-            } if contract_addr == OWNER && msg == &valid_msg
-        ));
 
-        // Another timeout packet will fail
-        let err = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap_err();
+        let proposal_id = 1;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            mock_ibc_execute_proposal(proposal_id),
+        )
+        .unwrap();
+        let packet = SatelliteMsg::ExecuteProposal {
+            id: proposal_id,
+            messages: vec![],
+            sent_at: None,
+            epoch: None,
+            mode: ExecutionMode::Atomic,
+        };
+        let retry_msg = ExecuteMsg::RetryProposal {
+            id: proposal_id,
+            channel: channel_id.to_string(),
+        };
+
+        // Proposal in progress can't be retried
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), retry_msg.clone()).unwrap_err();
         assert_eq!(
             err,
-            StdError::generic_err(format!(
-                "Proposal id: {} state is already {}",
-                proposal_id,
-                ProposalStatus::Failed
-            ))
+            ContractError::ProposalNotRetryable {
+                id: proposal_id,
+                channel: channel_id.to_string()
+            }
         );
 
-        // timeout msg with unknown proposal id will fail
-        let timeout_msg = mock_ibc_packet_timeout(
+        // Timed out proposal is retryable thus Assembly is not notified
+        let timeout_msg = mock_ibc_packet_timeout(channel_id, &packet).unwrap();
+        let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg.clone()).unwrap();
+        assert!(resp.messages.is_empty());
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            retry_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), retry_msg.clone()).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        assert!(resp.attributes.contains(&attr("attempt", "2")));
+
+        // Error acknowledgements are final by default
+        let ack_msg = mock_ibc_packet_ack(
             channel_id,
-            &SatelliteMsg::ExecuteProposal {
-                id: 128,
-                messages: vec![],
-            },
+            &packet,
+            IbcAcknowledgement::encode_json(&IbcAckResult::Error("Some error".to_string()))
+                .unwrap(),
         )
         .unwrap();
-        let err = ibc_packet_timeout(deps.as_mut(), env, timeout_msg).unwrap_err();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        assert_eq!(
+            PROPOSAL_STATE
+                .load(deps.as_ref().storage, proposal_id)
+                .unwrap(),
+            ProposalStatus::Failed
+        );
+
+        let err = execute(deps.as_mut(), env.clone(), info, retry_msg).unwrap_err();
         assert_eq!(
             err,
-            StdError::generic_err("Proposal 128 was not executed via controller")
+            ContractError::ProposalNotRetryable {
+                id: proposal_id,
+                channel: channel_id.to_string()
+            }
+        );
+
+        // Both attempts are kept in the error history
+        let errors: Vec<ProposalError> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ProposalError { id: proposal_id },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            errors,
+            vec![
+                ProposalError {
+                    proposal_id,
+                    channel_id: channel_id.to_string(),
+                    attempt: 1,
+                    time: env.block.time,
+                    error: "Packet timed out".to_string(),
+                    code: None,
+                    message_index: None,
+                },
+                ProposalError {
+                    proposal_id,
+                    channel_id: channel_id.to_string(),
+                    attempt: 2,
+                    time: env.block.time,
+                    error: "Some error".to_string(),
+                    // Plain text errors are acknowledged by v1 satellites
+                    code: Some(AckErrorCode::Unknown),
+                    message_index: None,
+                }
+            ]
+        );
+
+        let errors: Vec<ProposalError> = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::Errors {
+                    start_after: Some((proposal_id, channel_id.to_string(), 1)),
+                    limit: None,
+                },
+            )
+            .unwrap(),
         )
+        .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].attempt, 2);
     }
 
     #[test]
-    fn channel_heartbeat_timeout() {
+    fn abandon_proposal() {
         let (mut deps, env, info) = mock_all(OWNER);
         init_contract(&mut deps, env.clone(), info.clone());
-
         let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
 
-        let msg = mock_ibc_heartbeat(channel_id);
-        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let update_config = |retry_error_acks| {
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                max_proposal_attempts: Some(3),
+                retry_error_acks: Some(retry_error_acks),
+                heartbeat_interval: None,
+                heartbeat_timeout: None,
+                max_chunk_size: None,
+                epoch: None,
+            })
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_config(true),
+        )
+        .unwrap();
 
-        let timeout_msg = mock_ibc_packet_timeout(channel_id, &SatelliteMsg::Heartbeat {}).unwrap();
-        let resp = ibc_packet_timeout(deps.as_mut(), env, timeout_msg).unwrap();
-        assert!(resp
-            .attributes
-            .contains(&attr("action", "heartbeat_timeout".to_string())));
-        assert!(resp.attributes.contains(&attr("channel_id", channel_id)));
-    }
+        let packet = |id| SatelliteMsg::ExecuteProposal {
+            id,
+            messages: vec![],
+            sent_at: None,
+            epoch: None,
+            mode: ExecutionMode::Atomic,
+        };
+        for id in 1..=3 {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                mock_ibc_execute_proposal(id),
+            )
+            .unwrap();
+        }
+        for id in 1..=2 {
+            let timeout_msg = mock_ibc_packet_timeout(channel_id, &packet(id)).unwrap();
+            ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap();
+        }
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            &packet(3),
+            IbcAcknowledgement::encode_json(&IbcAckResult::Error("Some error".to_string()))
+                .unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+        assert!(resp.messages.is_empty());
 
-    #[test]
-    fn channel_close() {
-        let close_msg =
-            mock_ibc_channel_close_init("channel-0", IbcOrder::Unordered, IBC_APP_VERSION);
-        let (mut deps, env, _) = mock_all("random");
-        let err = ibc_channel_close(deps.as_mut(), env, close_msg).unwrap_err();
+        let abandon_msg = ExecuteMsg::AbandonProposal {
+            id: 1,
+            channel: channel_id.to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            abandon_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            abandon_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            resp.messages,
+            vec![confirm_assembly(&Addr::unchecked(OWNER), 1, ProposalStatus::Failed).unwrap()]
+        );
+        assert_eq!(
+            PROPOSAL_STATE.load(deps.as_ref().storage, 1).unwrap(),
+            ProposalStatus::Failed
+        );
+
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), abandon_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ProposalNotRetryable {
+                id: 1,
+                channel: channel_id.to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::RetryProposal {
+                id: 1,
+                channel: channel_id.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ProposalNotRetryable {
+                id: 1,
+                channel: channel_id.to_string()
+            }
+        );
+
+        // Error acknowledgements are not retryable anymore
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_config(false),
+        )
+        .unwrap();
+        assert_eq!(
+            resp.messages,
+            vec![confirm_assembly(&Addr::unchecked(OWNER), 3, ProposalStatus::Failed).unwrap()]
+        );
+
+        // Proposals can't be retried via the disabled satellite
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::UpdateSatellite {
+                alias: SATELLITE.to_string(),
+                channel_id: None,
+                port_id: None,
+                connection_id: None,
+                enabled: Some(false),
+                max_signal_outage: None,
+                timeout: None,
+                clear_timeout: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            resp.messages,
+            vec![confirm_assembly(&Addr::unchecked(OWNER), 2, ProposalStatus::Failed).unwrap()]
+        );
+        assert_eq!(
+            PROPOSAL_STATE.load(deps.as_ref().storage, 2).unwrap(),
+            ProposalStatus::Failed
+        );
+        let dispatch = PROPOSAL_DISPATCHES
+            .load(deps.as_ref().storage, (2, channel_id))
+            .unwrap();
+        assert!(!dispatch.retryable);
+
+        let counts: ProposalCountsResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::ProposalCounts {}).unwrap()).unwrap();
+        let failed = StatusCounts {
+            in_progress: 0,
+            executed: 0,
+            failed: 3,
+        };
+        assert_eq!(counts.total, failed);
+        assert_eq!(
+            counts.channels,
+            vec![ChannelStatusCounts {
+                channel_id: channel_id.to_string(),
+                counts: failed,
+            }]
+        );
+    }
+
+    #[test]
+    fn channel_heartbeat_ack() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        let msg = mock_ibc_heartbeat();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Ok acknowledgment
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            &SatelliteMsg::Heartbeat {},
+            IbcAcknowledgement::encode_json(&IbcAckResult::Ok(Binary::default())).unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+
+        assert!(resp
+            .attributes
+            .contains(&attr("action", "heartbeat_ack".to_string())));
+        assert!(resp.attributes.contains(&attr("channel_id", channel_id)));
+
+        let query_health = |deps: Deps, env: Env| -> SatelliteHealthResponse {
+            let mut health: Vec<SatelliteHealthResponse> = from_json(
+                query(
+                    deps,
+                    env,
+                    QueryMsg::SatelliteHealth {
+                        start_after: None,
+                        limit: None,
+                        warning_margin: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            health.pop().unwrap()
+        };
+
+        let health = query_health(deps.as_ref(), env.clone());
+        assert_eq!(
+            health.health,
+            ChannelHealth {
+                last_heartbeat_sent: Some(env.block.time),
+                last_ack: Some(env.block.time),
+                consecutive_timeouts: 0,
+                last_error: None,
+            }
+        );
+        assert!(!health.nearing_outage);
+
+        // The satellite is flagged once the outage deadline is within the warning margin
+        let mut env = env;
+        env.block.time = health
+            .outage_deadline
+            .unwrap()
+            .minus_seconds(DEFAULT_OUTAGE_WARNING_MARGIN);
+        assert!(query_health(deps.as_ref(), env).nearing_outage);
+    }
+
+    #[test]
+    fn permissionless_heartbeats() {
+        let (mut deps, mut env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        register_satellite(&mut deps, env.clone(), SATELLITE, "channel-0");
+        register_satellite(&mut deps, env.clone(), "osmosis", "channel-1");
+        register_satellite(&mut deps, env.clone(), "terra", "channel-2");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateSatellite {
+                alias: "terra".to_string(),
+                channel_id: None,
+                port_id: None,
+                connection_id: None,
+                enabled: Some(false),
+                max_signal_outage: None,
+                timeout: None,
+                clear_timeout: None,
+            },
+        )
+        .unwrap();
+
+        let keeper = mock_info("keeper", &[]);
+        let interval = CONFIG
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .heartbeat_interval;
+
+        // Disabled satellites are skipped
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            ExecuteMsg::SendHeartbeats {},
+        )
+        .unwrap();
+        assert_eq!(resp.messages.len(), 2);
+        assert!(resp.attributes.contains(&attr("channel", "channel-0")));
+        assert!(resp.attributes.contains(&attr("channel", "channel-1")));
+
+        // Nothing is due until the interval passes
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            ExecuteMsg::SendHeartbeats {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoHeartbeatsDue {});
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            mock_ibc_heartbeat(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::HeartbeatTooEarly {
+                channel_id: "channel-0".to_string()
+            }
+        );
+
+        // The owner is not rate limited
+        env.block.time = env.block.time.plus_seconds(1);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            mock_ibc_heartbeat(),
+        )
+        .unwrap();
+
+        // Only the satellite pinged earlier by the keeper is due
+        env.block.time = env.block.time.plus_seconds(interval - 1);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            ExecuteMsg::SendHeartbeats {},
+        )
+        .unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        assert!(resp.attributes.contains(&attr("channel", "channel-1")));
+
+        // The interval can't exceed the lowest signal outage
+        let err = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                max_proposal_attempts: None,
+                retry_error_acks: None,
+                heartbeat_interval: Some(MAX_HEARTBEAT_INTERVAL + 1),
+                heartbeat_timeout: None,
+                max_chunk_size: None,
+                epoch: None,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::HeartbeatIntervalError {});
+    }
+
+    #[test]
+    fn channel_proposal_timeout() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+        let proposal_id = 1;
+
+        let msg = mock_ibc_execute_proposal(proposal_id);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let timeout_msg = mock_ibc_packet_timeout(
+            channel_id,
+            &SatelliteMsg::ExecuteProposal {
+                id: proposal_id,
+                messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg.clone()).unwrap();
+        assert!(resp
+            .attributes
+            .contains(&attr("proposal_id", proposal_id.to_string())));
+
+        let state = PROPOSAL_STATE
+            .load(deps.as_ref().storage, proposal_id)
+            .unwrap();
+        assert_eq!(state, ProposalStatus::Failed);
+
+        assert_eq!(resp.messages.len(), 1);
+        let valid_msg = to_json_binary(&AssemblyExecuteMsg::IBCProposalCompleted {
+            proposal_id,
+            status: ProposalStatus::Failed,
+        })
+        .unwrap();
+        assert!(matches!(
+            &resp.messages[0],
+            SubMsg {
+                msg: CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }),
+                ..
+            } if contract_addr == OWNER && msg == &valid_msg
+        ));
+
+        // Another timeout packet will fail
+        let err = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(format!(
+                "Proposal id: {} state is already {}",
+                proposal_id,
+                ProposalStatus::Failed
+            ))
+        );
+
+        // timeout msg with unknown proposal id will fail
+        let timeout_msg = mock_ibc_packet_timeout(
+            channel_id,
+            &SatelliteMsg::ExecuteProposal {
+                id: 128,
+                messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+        )
+        .unwrap();
+        let err = ibc_packet_timeout(deps.as_mut(), env, timeout_msg).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Proposal 128 was not executed via controller")
+        )
+    }
+
+    #[test]
+    fn legacy_proposal_outcome() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info);
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        // Proposals relayed before v1.2.0 have the aggregated state only
+        save_proposal_status(deps.as_mut().storage, 1, &ProposalStatus::InProgress).unwrap();
+        save_proposal_status(deps.as_mut().storage, 2, &ProposalStatus::InProgress).unwrap();
+
+        let packet = |id| SatelliteMsg::ExecuteProposal {
+            id,
+            messages: vec![],
+            sent_at: None,
+            epoch: None,
+            mode: ExecutionMode::Atomic,
+        };
+
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            &packet(1),
+            IbcAcknowledgement::encode_json(&IbcAckResult::Ok(Binary::default())).unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg.clone()).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        let state = PROPOSAL_STATE.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(state, ProposalStatus::Executed);
+
+        // The outcome is recorded only once
+        let err = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(format!(
+                "Proposal id: 1 state is already {}",
+                ProposalStatus::Executed
+            ))
+        );
+
+        let timeout_msg = mock_ibc_packet_timeout(channel_id, &packet(2)).unwrap();
+        let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        let state = PROPOSAL_STATE.load(deps.as_ref().storage, 2).unwrap();
+        assert_eq!(state, ProposalStatus::Failed);
+
+        let errors: Vec<ProposalError> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ProposalError { id: 2 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            errors,
+            vec![ProposalError {
+                proposal_id: 2,
+                channel_id: channel_id.to_string(),
+                attempt: 1,
+                time: env.block.time,
+                error: "Packet timed out".to_string(),
+                code: None,
+                message_index: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn channel_heartbeat_timeout() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        let msg = mock_ibc_heartbeat();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let timeout_msg = mock_ibc_packet_timeout(channel_id, &SatelliteMsg::Heartbeat {}).unwrap();
+        let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg.clone()).unwrap();
+        assert!(resp
+            .attributes
+            .contains(&attr("action", "heartbeat_timeout".to_string())));
+        assert!(resp.attributes.contains(&attr("channel_id", channel_id)));
+
+        ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap();
+        let health = SATELLITE_HEALTH
+            .load(deps.as_ref().storage, channel_id)
+            .unwrap();
+        assert_eq!(health.consecutive_timeouts, 2);
+        assert_eq!(health.last_ack, None);
+
+        // An error acknowledgement ends the timeout streak, though it is not a successful one
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            &SatelliteMsg::Heartbeat {},
+            IbcAcknowledgement::encode_json(&IbcAckResult::Error("Some error".to_string()))
+                .unwrap(),
+        )
+        .unwrap();
+        ibc_packet_ack(deps.as_mut(), env, ack_msg).unwrap();
+        let health = SATELLITE_HEALTH
+            .load(deps.as_ref().storage, channel_id)
+            .unwrap();
+        assert_eq!(health.consecutive_timeouts, 0);
+        assert_eq!(health.last_ack, None);
+        assert_eq!(health.last_error, Some("Some error".to_string()));
+    }
+
+    #[test]
+    fn satellite_reports() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info);
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        // Reports from channels which don't serve a registered satellite are refused
+        connect_channel(&mut deps, env.clone(), "channel-1");
+        let msg = mock_ibc_packet_recv(
+            "channel-1",
+            &SatelliteReport::Balances(BalanceReport {
+                time: env.block.time,
+                balances: coins(100, "astro"),
+            }),
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcAckResult = from_json(resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            IbcAckResult::Error(
+                "Generic error: No satellite is registered for channel channel-1".to_string()
+            )
+        );
+        assert!(!REPORTS.has(deps.as_ref().storage, "channel-1"));
+
+        // Malformed packets are acknowledged with an error
+        let msg = mock_ibc_packet_recv(channel_id, &SatelliteMsg::Heartbeat {}).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcAckResult = from_json(resp.acknowledgement).unwrap();
+        assert!(matches!(ack, IbcAckResult::Error(_)));
+
+        let status = StatusReport {
+            time: env.block.time,
+            version: "1.3.0".to_string(),
+            latest_hub_signal_time: env.block.time,
+            last_executed_proposal: Some(5),
+        };
+        let balances = BalanceReport {
+            time: env.block.time,
+            balances: coins(100, "astro"),
+        };
+        for report in [
+            SatelliteReport::Status(status.clone()),
+            SatelliteReport::Balances(balances.clone()),
+        ] {
+            let msg = mock_ibc_packet_recv(channel_id, &report).unwrap();
+            let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+            let ack: IbcAckResult = from_json(resp.acknowledgement).unwrap();
+            assert_eq!(ack, IbcAckResult::Ok(b"ok".into()));
+        }
+
+        // The latest report of each kind is kept
+        let balances = BalanceReport {
+            time: env.block.time.plus_seconds(1),
+            balances: coins(200, "astro"),
+        };
+        let msg =
+            mock_ibc_packet_recv(channel_id, &SatelliteReport::Balances(balances.clone())).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(resp.attributes.contains(&attr("report", "balances")));
+
+        let reports: ChannelReports = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Reports {
+                    channel_id: channel_id.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            reports,
+            ChannelReports {
+                channel_id: channel_id.to_string(),
+                status: Some(status),
+                balances: Some(balances),
+                config: None,
+            }
+        );
+
+        let all_reports: Vec<ChannelReports> = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::AllReports {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(all_reports, vec![reports]);
+    }
+
+    #[test]
+    fn queued_proposal_outcome() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        for proposal_id in [1, 2] {
+            let msg = mock_ibc_execute_proposal(proposal_id);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+            // The satellite timelock delays the execution
+            let ack_msg = mock_ibc_packet_ack(
+                channel_id,
+                &SatelliteMsg::ExecuteProposal {
+                    id: proposal_id,
+                    messages: vec![],
+                    sent_at: None,
+                    epoch: None,
+                    mode: ExecutionMode::Atomic,
+                },
+                IbcAcknowledgement::encode_json(&IbcAckResult::Ok(QUEUED_ACK.into())).unwrap(),
+            )
+            .unwrap();
+            let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+            assert!(resp.messages.is_empty());
+            assert!(resp.attributes.contains(&attr("action", "proposal_queued")));
+            let state = PROPOSAL_STATE
+                .load(deps.as_ref().storage, proposal_id)
+                .unwrap();
+            assert_eq!(state, ProposalStatus::InProgress);
+        }
+
+        // Queued proposals can't be finalized by the satellite until they're executed
+        let report = SatelliteReport::ProposalOutcome(ProposalOutcomeReport {
+            time: env.block.time,
+            id: 1,
+            status: QueueStatus::Queued,
+        });
+        let msg = mock_ibc_packet_recv(channel_id, &report).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcAckResult = from_json(resp.acknowledgement).unwrap();
+        assert!(matches!(ack, IbcAckResult::Error(_)));
+
+        for (proposal_id, status, expected) in [
+            (1, QueueStatus::Executed, ProposalStatus::Executed),
+            (2, QueueStatus::Vetoed, ProposalStatus::Failed),
+        ] {
+            let report = SatelliteReport::ProposalOutcome(ProposalOutcomeReport {
+                time: env.block.time,
+                id: proposal_id,
+                status,
+            });
+            let msg = mock_ibc_packet_recv(channel_id, &report).unwrap();
+            let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+            let ack: IbcAckResult = from_json(resp.acknowledgement).unwrap();
+            assert_eq!(ack, IbcAckResult::Ok(b"ok".into()));
+            assert!(resp
+                .attributes
+                .contains(&attr("report", "proposal_outcome")));
+
+            let valid_msg = to_json_binary(&AssemblyExecuteMsg::IBCProposalCompleted {
+                proposal_id,
+                status: expected.clone(),
+            })
+            .unwrap();
+            assert!(matches!(
+                &resp.messages[0],
+                SubMsg {
+                    msg: CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }),
+                    ..
+                } if contract_addr == OWNER && msg == &valid_msg
+            ));
+            let state = PROPOSAL_STATE
+                .load(deps.as_ref().storage, proposal_id)
+                .unwrap();
+            assert_eq!(state, expected);
+        }
+
+        let dispatch = PROPOSAL_DISPATCHES
+            .load(deps.as_ref().storage, (2, channel_id))
+            .unwrap();
+        assert_eq!(dispatch.error.unwrap(), "Proposal was vetoed");
+    }
+
+    #[test]
+    fn batch_proposal_ack() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        let proposals: Vec<_> = (1..=3)
+            .map(|id| BatchProposal {
+                id,
+                messages: vec![],
+                mode: ExecutionMode::Atomic,
+            })
+            .collect();
+        let batch_msg = |proposals: Vec<BatchProposal>| ExecuteMsg::IbcExecuteBatch {
+            satellite: SATELLITE.to_string(),
+            proposals,
+            timeout: None,
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_msg(vec![proposals[0].clone(), proposals[0].clone()]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ProposalAlreadyExists { proposal_id: 1 });
+
+        // A single packet is sent for the whole batch
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            batch_msg(proposals.clone()),
+        )
+        .unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        for id in 1..=3 {
+            let state = PROPOSAL_STATE.load(deps.as_ref().storage, id).unwrap();
+            assert_eq!(state, ProposalStatus::InProgress);
+        }
+
+        let results = vec![
+            BatchProposalResult {
+                id: 1,
+                result: IbcAckResult::Ok(b"ok".into()),
+            },
+            BatchProposalResult {
+                id: 2,
+                result: IbcAckResult::Error("Some error".to_string()),
+            },
+            BatchProposalResult {
+                id: 3,
+                result: IbcAckResult::Ok(QUEUED_ACK.into()),
+            },
+        ];
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            &SatelliteMsg::ExecuteBatch {
+                proposals,
+                sent_at: env.block.time,
+                epoch: 0,
+            },
+            IbcAcknowledgement::encode_json(&IbcAckResult::Ok(to_json_binary(&results).unwrap()))
+                .unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+
+        // Assembly is notified about every completed proposal separately
+        let expected = [(1, ProposalStatus::Executed), (2, ProposalStatus::Failed)];
+        assert_eq!(resp.messages.len(), expected.len());
+        for (msg, (proposal_id, status)) in resp.messages.iter().zip(expected) {
+            let valid_msg = to_json_binary(&AssemblyExecuteMsg::IBCProposalCompleted {
+                proposal_id,
+                status: status.clone(),
+            })
+            .unwrap();
+            assert!(matches!(
+                msg,
+                SubMsg {
+                    msg: CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }),
+                    ..
+                } if contract_addr == OWNER && msg == &valid_msg
+            ));
+            let state = PROPOSAL_STATE
+                .load(deps.as_ref().storage, proposal_id)
+                .unwrap();
+            assert_eq!(state, status);
+        }
+
+        // The queued proposal waits for the satellite outcome report
+        let state = PROPOSAL_STATE.load(deps.as_ref().storage, 3).unwrap();
+        assert_eq!(state, ProposalStatus::InProgress);
+        assert!(resp.attributes.contains(&attr("queued_proposal_id", "3")));
+        let dispatch = PROPOSAL_DISPATCHES
+            .load(deps.as_ref().storage, (2, channel_id))
+            .unwrap();
+        assert_eq!(dispatch.error.unwrap(), "Some error");
+    }
+
+    #[test]
+    fn chunked_proposal() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        let mut update_config = UpdateConfigMsg {
+            max_proposal_attempts: None,
+            retry_error_acks: None,
+            heartbeat_interval: None,
+            heartbeat_timeout: None,
+            max_chunk_size: Some(100),
+            epoch: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(update_config.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ChunkSizeError {});
+
+        update_config.max_chunk_size = Some(1024);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(update_config),
+        )
+        .unwrap();
+
+        // The proposal exceeding the max chunk size is split into chunks
+        let proposal_id = 1;
+        let messages = vec![CosmosMsg::Stargate {
+            type_url: "/cosmwasm.wasm.v1.MsgStoreCode".to_string(),
+            value: Binary::from(vec![1; 2000]),
+        }];
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::IbcExecuteProposal {
+                satellite: SATELLITE.to_string(),
+                channel_id: None,
+                proposal_id,
+                messages: messages.clone(),
+                timeout: None,
+                mode: None,
+            },
+        )
+        .unwrap();
+        let chunks: Vec<SatelliteMsg> = resp
+            .messages
+            .iter()
+            .map(|msg| match &msg.msg {
+                CosmosMsg::Ibc(IbcMsg::SendPacket { data, .. }) => from_json(data).unwrap(),
+                _ => panic!("Unexpected message"),
+            })
+            .collect();
+        assert_eq!(
+            chunks,
+            SatelliteMsg::chunks(
+                proposal_id,
+                &messages,
+                1024,
+                env.block.time,
+                0,
+                ExecutionMode::Atomic,
+                1
+            )
+            .unwrap()
+        );
+        assert!(chunks.len() > 1);
+
+        // Chunks which didn't complete the upload leave the proposal in progress
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            &chunks[0],
+            IbcAcknowledgement::encode_json(&IbcAckResult::Ok(CHUNK_ACK.into())).unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+        assert!(resp.messages.is_empty());
+        let state = PROPOSAL_STATE
+            .load(deps.as_ref().storage, proposal_id)
+            .unwrap();
+        assert_eq!(state, ProposalStatus::InProgress);
+
+        // The chunk which completed the upload carries the proposal result
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            chunks.last().unwrap(),
+            IbcAcknowledgement::encode_json(&IbcAckResult::Ok(b"ok".into())).unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        let state = PROPOSAL_STATE
+            .load(deps.as_ref().storage, proposal_id)
+            .unwrap();
+        assert_eq!(state, ProposalStatus::Executed);
+
+        // Late timeouts of other chunks don't affect the finished proposal
+        let timeout_msg = mock_ibc_packet_timeout(channel_id, &chunks[0]).unwrap();
+        let resp = ibc_packet_timeout(deps.as_mut(), env, timeout_msg).unwrap();
+        assert!(resp.messages.is_empty());
+        let state = PROPOSAL_STATE
+            .load(deps.as_ref().storage, proposal_id)
+            .unwrap();
+        assert_eq!(state, ProposalStatus::Executed);
+    }
+
+    #[test]
+    fn chunked_proposal_retry() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                max_proposal_attempts: Some(2),
+                retry_error_acks: None,
+                heartbeat_interval: None,
+                heartbeat_timeout: None,
+                max_chunk_size: Some(1024),
+                epoch: None,
+            }),
+        )
+        .unwrap();
+
+        let proposal_id = 1;
+        let sent_chunks = |resp: Response| -> Vec<SatelliteMsg> {
+            resp.messages
+                .iter()
+                .map(|msg| match &msg.msg {
+                    CosmosMsg::Ibc(IbcMsg::SendPacket { data, .. }) => from_json(data).unwrap(),
+                    _ => panic!("Unexpected message"),
+                })
+                .collect()
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::IbcExecuteProposal {
+                satellite: SATELLITE.to_string(),
+                channel_id: None,
+                proposal_id,
+                messages: vec![CosmosMsg::Stargate {
+                    type_url: "/cosmwasm.wasm.v1.MsgStoreCode".to_string(),
+                    value: Binary::from(vec![1; 2000]),
+                }],
+                timeout: None,
+                mode: None,
+            },
+        )
+        .unwrap();
+        let first_attempt = sent_chunks(resp);
+        assert!(first_attempt.len() > 1);
+
+        // A lost chunk fails the first attempt while the other chunks are still in flight
+        let timeout_msg = mock_ibc_packet_timeout(channel_id, &first_attempt[0]).unwrap();
+        ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap();
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::RetryProposal {
+                id: proposal_id,
+                channel: channel_id.to_string(),
+            },
+        )
+        .unwrap();
+        let second_attempt = sent_chunks(resp);
+        assert!(second_attempt
+            .iter()
+            .all(|chunk| matches!(chunk, SatelliteMsg::ExecuteChunk { attempt: 2, .. })));
+
+        // Late outcomes of the first attempt don't affect the retried one
+        let timeout_msg = mock_ibc_packet_timeout(channel_id, &first_attempt[1]).unwrap();
+        let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap();
+        assert!(resp.messages.is_empty());
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            first_attempt.last().unwrap(),
+            IbcAcknowledgement::encode_json(&IbcAckResult::Error("Some error".to_string()))
+                .unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+        assert!(resp.messages.is_empty());
+
+        let dispatch = PROPOSAL_DISPATCHES
+            .load(deps.as_ref().storage, (proposal_id, channel_id))
+            .unwrap();
+        assert_eq!(dispatch.status, ProposalStatus::InProgress);
+        assert_eq!(dispatch.attempts, 2);
+
+        // The chunk which completed the retried upload carries the proposal result
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            second_attempt.last().unwrap(),
+            IbcAcknowledgement::encode_json(&IbcAckResult::Ok(b"ok".into())).unwrap(),
+        )
+        .unwrap();
+        let resp = ibc_packet_ack(deps.as_mut(), env, ack_msg).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        let state = PROPOSAL_STATE
+            .load(deps.as_ref().storage, proposal_id)
+            .unwrap();
+        assert_eq!(state, ProposalStatus::Executed);
+    }
+
+    #[test]
+    fn channel_version_negotiation() {
+        let (mut deps, env, _) = mock_all(OWNER);
+
+        let open = |deps: DepsMut, msg: IbcChannelOpenMsg| -> StdResult<String> {
+            ibc_channel_open(deps, mock_env(), msg).map(|res| res.unwrap().version)
+        };
+
+        // OpenInit proposes the latest version unless the relayer requested a specific one
+        let msg = mock_ibc_channel_open_init("channel-0", IBC_ORDERING, "");
+        assert_eq!(
+            open(deps.as_mut(), msg).unwrap(),
+            IbcVersion::LATEST.as_str()
+        );
+        let msg = mock_ibc_channel_open_init("channel-0", IBC_ORDERING, IbcVersion::V1.as_str());
+        assert_eq!(open(deps.as_mut(), msg).unwrap(), IbcVersion::V1.as_str());
+        let msg = mock_ibc_channel_open_init("channel-0", IBC_ORDERING, "ics20-1");
+        assert_eq!(
+            open(deps.as_mut(), msg).unwrap_err(),
+            StdError::generic_err("Version `ics20-1` is not supported")
+        );
+
+        // OpenTry picks the highest common version
+        let msg = mock_ibc_channel_open_try("channel-0", IBC_ORDERING, "astroport-ibc-v3");
+        assert_eq!(
+            open(deps.as_mut(), msg).unwrap(),
+            IbcVersion::LATEST.as_str()
+        );
+        let msg = mock_ibc_channel_open_try("channel-0", IBC_ORDERING, IbcVersion::V1.as_str());
+        assert_eq!(open(deps.as_mut(), msg).unwrap(), IbcVersion::V1.as_str());
+        let msg = mock_ibc_channel_open_try("channel-0", IBC_ORDERING, "astroport-ibc-v0");
+        assert_eq!(
+            open(deps.as_mut(), msg).unwrap_err(),
+            StdError::generic_err("Counterparty version `astroport-ibc-v0` is not supported")
+        );
+
+        // The negotiated version is stored per channel
+        let msg = mock_ibc_channel_connect_ack("channel-0", IBC_ORDERING, IbcVersion::V1.as_str());
+        let resp = ibc_channel_connect(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(resp
+            .attributes
+            .contains(&attr("version", "astroport-ibc-v1")));
+        let msg = mock_ibc_channel_connect_ack("channel-1", IBC_ORDERING, IbcVersion::V2.as_str());
+        ibc_channel_connect(deps.as_mut(), env, msg).unwrap();
+
+        let storage = deps.as_ref().storage;
+        assert_eq!(
+            channel_version(storage, "channel-0").unwrap(),
+            IbcVersion::V1
+        );
+        assert_eq!(
+            channel_version(storage, "channel-1").unwrap(),
+            IbcVersion::V2
+        );
+    }
+
+    #[test]
+    fn channel_close() {
+        let close_msg =
+            mock_ibc_channel_close_init("channel-0", IbcOrder::Unordered, IbcVersion::V1.as_str());
+        let (mut deps, env, _) = mock_all("random");
+        let err = ibc_channel_close(deps.as_mut(), env, close_msg).unwrap_err();
         assert_eq!(err, StdError::generic_err("Closing channel is not allowed"))
     }
+
+    #[test]
+    fn proposal_simulation() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        let simulate_msg = ExecuteMsg::IbcSimulateProposal {
+            satellite: SATELLITE.to_string(),
+            messages: vec![],
+            timeout: None,
+        };
+        // Anyone can simulate, but only once per interval
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            simulate_msg.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            simulate_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SimulationTooEarly {
+                channel_id: channel_id.to_string(),
+                next_simulation: env.block.time.plus_seconds(SIMULATION_INTERVAL),
+            }
+        );
+
+        // The owner is not rate limited and simulations get sequential ids
+        for id in 2..=4u64 {
+            let resp = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                simulate_msg.clone(),
+            )
+            .unwrap();
+            assert_eq!(resp.data, Some(to_json_binary(&id).unwrap()));
+            assert_eq!(resp.messages.len(), 1);
+        }
+
+        let results = [
+            (
+                1,
+                IbcAckResult::Ok(to_json_binary(&SimulationResult::Passed {}).unwrap()),
+                SimulationStatus::Passed,
+            ),
+            (
+                2,
+                IbcAckResult::Ok(
+                    to_json_binary(&SimulationResult::Failed {
+                        error: "Some error".to_string(),
+                    })
+                    .unwrap(),
+                ),
+                SimulationStatus::Failed {
+                    error: "Some error".to_string(),
+                },
+            ),
+            // Malformed results fail the simulation instead of the acknowledgement
+            (
+                3,
+                IbcAckResult::Ok(b"ok".into()),
+                SimulationStatus::Failed {
+                    error: format!(
+                        "Invalid simulation result: {}",
+                        from_json::<SimulationResult>(b"ok").unwrap_err()
+                    ),
+                },
+            ),
+        ];
+        for (id, ack, status) in results {
+            let ack_msg = mock_ibc_packet_ack(
+                channel_id,
+                &SatelliteMsg::SimulateProposal {
+                    id,
+                    messages: vec![],
+                },
+                IbcAcknowledgement::encode_json(&ack).unwrap(),
+            )
+            .unwrap();
+            let resp = ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+            // Simulations never notify Assembly
+            assert!(resp.messages.is_empty());
+
+            let simulation: SimulationInfo =
+                from_json(query(deps.as_ref(), env.clone(), QueryMsg::Simulation { id }).unwrap())
+                    .unwrap();
+            assert_eq!(simulation.status, status);
+            assert_eq!(simulation.finished_at, Some(env.block.time));
+        }
+
+        let timeout_msg = mock_ibc_packet_timeout(
+            channel_id,
+            &SatelliteMsg::SimulateProposal {
+                id: 4,
+                messages: vec![],
+            },
+        )
+        .unwrap();
+        ibc_packet_timeout(deps.as_mut(), env.clone(), timeout_msg).unwrap();
+
+        let simulations: Vec<SimulationInfo> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Simulations {
+                    start_after: Some(2),
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            simulations
+                .iter()
+                .map(|simulation| simulation.id)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(simulations[1].status, SimulationStatus::TimedOut);
+
+        let mut env = env;
+        env.block.time = env.block.time.plus_seconds(SIMULATION_INTERVAL);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            simulate_msg.clone(),
+        )
+        .unwrap();
+
+        // Simulations are not supported by the v1 protocol
+        CHANNELS
+            .update::<_, StdError>(deps.as_mut().storage, channel_id, |channel| {
+                let mut channel = channel.unwrap();
+                channel.version = IbcVersion::V1;
+                Ok(channel)
+            })
+            .unwrap();
+        execute(deps.as_mut(), env, info, simulate_msg).unwrap_err();
+    }
+
+    #[test]
+    fn execution_receipt() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        let receipt = ExecutionReceipt {
+            height: 100,
+            time: env.block.time.minus_seconds(10),
+            messages: vec![MessageResult {
+                data: Some(b"data".into()),
+                events_hash: "hash".to_string(),
+                error: None,
+            }],
+        };
+        let acks = [
+            (
+                1,
+                IbcAckResult::Ok(to_json_binary(&receipt).unwrap()),
+                Some(receipt),
+            ),
+            // v1 satellites don't report receipts
+            (2, IbcAckResult::Ok(b"ok".into()), None),
+        ];
+        for (proposal_id, ack, receipt) in acks {
+            let msg = mock_ibc_execute_proposal(proposal_id);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+            let ack_msg = mock_ibc_packet_ack(
+                channel_id,
+                &SatelliteMsg::ExecuteProposal {
+                    id: proposal_id,
+                    messages: vec![],
+                    sent_at: None,
+                    epoch: None,
+                    mode: ExecutionMode::Atomic,
+                },
+                IbcAcknowledgement::encode_json(&ack).unwrap(),
+            )
+            .unwrap();
+            ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+
+            let proposal: ProposalResponse = from_json(
+                query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::Proposal { id: proposal_id },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(proposal.status, ProposalStatus::Executed);
+            assert_eq!(proposal.dispatches[0].receipt, receipt);
+        }
+    }
+
+    #[test]
+    fn structured_error_ack() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(&mut deps, env.clone(), info.clone());
+
+        let channel_id = "channel-0";
+        register_satellite(&mut deps, env.clone(), SATELLITE, channel_id);
+
+        let proposal_id = 1;
+        let msg = mock_ibc_execute_proposal(proposal_id);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let error = AckError::message_failed(2, "codespace: wasm, code: 5");
+        let ack_msg = mock_ibc_packet_ack(
+            channel_id,
+            &SatelliteMsg::ExecuteProposal {
+                id: proposal_id,
+                messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+            IbcAcknowledgement::encode_json(&IbcAckResult::Error(error.encode().unwrap())).unwrap(),
+        )
+        .unwrap();
+        ibc_packet_ack(deps.as_mut(), env.clone(), ack_msg).unwrap();
+
+        // The raw error text is stored along with the code and the failing message
+        let proposal: ProposalResponse = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Proposal { id: proposal_id },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Failed);
+        assert_eq!(
+            proposal.dispatches[0].error.as_deref(),
+            Some("codespace: wasm, code: 5")
+        );
+
+        let errors: Vec<ProposalError> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ProposalError { id: proposal_id },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            errors,
+            vec![ProposalError {
+                proposal_id,
+                channel_id: channel_id.to_string(),
+                attempt: 1,
+                time: env.block.time,
+                error: "codespace: wasm, code: 5".to_string(),
+                code: Some(AckErrorCode::MessageFailed),
+                message_index: Some(2),
+            }]
+        );
+
+        // Codes added by newer satellites are decoded as unknown
+        let error = AckError::decode(
+            r#"{"code":"some_new_code","message_index":null,"error":"Some error"}"#,
+        );
+        assert_eq!(error, AckError::new(AckErrorCode::Unknown, "Some error"));
+    }
 }
@@ -1,6 +1,7 @@
 pub mod contract;
 mod error;
 pub mod ibc;
+mod migration;
 pub mod state;
 #[cfg(test)]
 mod test_utils;
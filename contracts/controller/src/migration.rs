@@ -0,0 +1,126 @@
+use astroport_ibc::{IbcVersion, SIGNAL_OUTAGE_LIMITS};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, DepsMut, Env, IbcQuery, ListChannelsResponse, Order, StdResult, Storage};
+use cw_storage_plus::Item;
+use ibc_controller_package::{ChannelInfo, ProposalError, SatelliteInfo, StatusCounts};
+
+use crate::state::{
+    Config, CHANNELS, CONFIG, DEFAULT_HEARTBEAT_INTERVAL, DEFAULT_MAX_PROPOSAL_ATTEMPTS,
+    LAST_ERROR, PROPOSAL_COUNTS, PROPOSAL_ERRORS, PROPOSAL_STATE, SATELLITES, SATELLITE_CHANNELS,
+    STATUS_PROPOSALS,
+};
+
+/// Config layout used by ibc-controller v1.1.x
+#[cw_serde]
+struct ConfigV110 {
+    owner: Addr,
+    timeout: u64,
+}
+
+const CONFIG_V110: Item<ConfigV110> = Item::new("config");
+
+/// Fills the parameters introduced after v1.1.x with their default values
+pub fn migrate_config(storage: &mut dyn Storage) -> StdResult<()> {
+    let old_config = CONFIG_V110.load(storage)?;
+
+    CONFIG.save(
+        storage,
+        &Config {
+            owner: old_config.owner,
+            timeout: old_config.timeout,
+            heartbeat_timeout: old_config.timeout,
+            max_proposal_attempts: DEFAULT_MAX_PROPOSAL_ATTEMPTS,
+            retry_error_acks: false,
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            max_chunk_size: 0,
+            epoch: 0,
+        },
+    )
+}
+
+/// Copies the v1.1.x `LAST_ERROR` content into the per-proposal error storage.
+/// The proposal the error belongs to wasn't tracked, thus it is kept under proposal id 0
+/// and attempt 0 with an empty channel. The migration time is used as the error time.
+/// `LAST_ERROR` itself is still maintained for the `LastError` query.
+pub fn migrate_last_error(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let last_error = LAST_ERROR.may_load(storage)?.unwrap_or_default();
+    if last_error.is_empty() {
+        return Ok(());
+    }
+
+    PROPOSAL_ERRORS.save(
+        storage,
+        (0, "", 0),
+        &ProposalError {
+            proposal_id: 0,
+            channel_id: "".to_string(),
+            attempt: 0,
+            time: env.block.time,
+            error: last_error,
+            code: None,
+            message_index: None,
+        },
+    )
+}
+
+/// Builds the status counters and the status index for the proposals relayed before v1.2.0.
+/// Those proposals have no dispatch records, thus the channel indexes are left empty
+pub fn migrate_proposal_index(storage: &mut dyn Storage) -> StdResult<()> {
+    let proposals = PROPOSAL_STATE
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut counts = StatusCounts::default();
+    for (id, status) in proposals {
+        counts.add(&status);
+        STATUS_PROPOSALS.save(storage, (&status.to_string(), id), &())?;
+    }
+
+    PROPOSAL_COUNTS.save(storage, &counts)
+}
+
+/// Records the channels opened before v1.2.0, as they weren't stored on connect.
+/// The original connect time is unknown, thus the migration time is used.
+/// Every channel is registered as an enabled satellite aliased by the channel id,
+/// so proposals and heartbeats can be sent to it right after the upgrade.
+/// The signal outage limit is the shortest one allowed and can be changed with `UpdateSatellite`
+pub fn migrate_channels(deps: DepsMut, env: &Env) -> StdResult<()> {
+    let ListChannelsResponse { channels } = deps
+        .querier
+        .query(&IbcQuery::ListChannels { port_id: None }.into())?;
+
+    for channel in channels {
+        let channel_id = &channel.endpoint.channel_id;
+        if !SATELLITE_CHANNELS.has(deps.storage, channel_id) {
+            SATELLITE_CHANNELS.save(deps.storage, channel_id, channel_id)?;
+            SATELLITES.save(
+                deps.storage,
+                channel_id,
+                &SatelliteInfo {
+                    alias: channel_id.clone(),
+                    channel_id: channel_id.clone(),
+                    port_id: channel.counterparty_endpoint.port_id.clone(),
+                    connection_id: channel.connection_id.clone(),
+                    enabled: true,
+                    max_signal_outage: *SIGNAL_OUTAGE_LIMITS.start(),
+                    timeout: None,
+                },
+            )?;
+        }
+
+        CHANNELS.save(
+            deps.storage,
+            &channel.endpoint.channel_id,
+            &ChannelInfo {
+                channel_id: channel.endpoint.channel_id.clone(),
+                counterparty_port_id: channel.counterparty_endpoint.port_id,
+                counterparty_channel_id: channel.counterparty_endpoint.channel_id,
+                connection_id: channel.connection_id,
+                connected_at: env.block.time,
+                version: IbcVersion::parse(&channel.version).unwrap_or(IbcVersion::V1),
+            },
+        )?;
+    }
+
+    Ok(())
+}
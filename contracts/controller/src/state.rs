@@ -1,9 +1,35 @@
+use std::ops::RangeInclusive;
+
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, CosmosMsg, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Item, Map};
 
+use astro_satellite_package::{AckError, ExecutionReceipt, IbcVersion, SatelliteReport};
 use astroport::common::OwnershipProposal;
+use astroport_ibc::{SIGNAL_OUTAGE_LIMITS, TIMEOUT_LIMITS};
 use ibc_controller_package::astroport_governance::assembly::ProposalStatus;
+use ibc_controller_package::{
+    ChannelHealth, ChannelInfo, ChannelProposalStatus, ChannelReports, ChannelStatusCounts,
+    ExecutionMode, ProposalCountsResponse, ProposalDispatch, ProposalError, ProposalResponse,
+    SatelliteHealthResponse, SatelliteInfo, SimulationInfo, StatusCounts, UpdateConfigMsg,
+};
+
+use crate::error::ContractError;
+
+/// Max number of times a proposal can be sent to the same satellite
+pub const PROPOSAL_ATTEMPTS_LIMITS: RangeInclusive<u32> = 1..=10;
+
+/// By default a failed proposal is final and can't be retried
+pub const DEFAULT_MAX_PROPOSAL_ATTEMPTS: u32 = 1;
+
+/// Heartbeats must be sent more often than the lowest possible satellite signal outage
+pub const MAX_HEARTBEAT_INTERVAL: u64 = *SIGNAL_OUTAGE_LIMITS.start();
+
+/// 1 day
+pub const DEFAULT_HEARTBEAT_INTERVAL: u64 = 86400;
+
+/// Proposal chunks smaller than this would flood the channel with packets
+pub const MIN_CHUNK_SIZE: u64 = 1024;
 
 #[cw_serde]
 pub struct Config {
@@ -11,13 +37,773 @@ pub struct Config {
     pub owner: Addr,
     /// when packet times out, measured on remote chain
     pub timeout: u64,
+    /// when heartbeat packet times out, measured on remote chain
+    pub heartbeat_timeout: u64,
+    /// Max number of times a proposal can be sent to the same satellite
+    pub max_proposal_attempts: u32,
+    /// Whether proposals failed with an error acknowledgement can be retried
+    pub retry_error_acks: bool,
+    /// Min number of seconds between two permissionless heartbeats sent to the same satellite
+    pub heartbeat_interval: u64,
+    /// Max size in bytes of the proposal messages sent in a single packet. 0 disables chunking
+    pub max_chunk_size: u64,
+    /// Epoch carried by the proposal packets. Satellites reject proposals of older epochs
+    pub epoch: u64,
+}
+
+impl Config {
+    pub(crate) fn update(&mut self, params: UpdateConfigMsg) -> Result<(), ContractError> {
+        if let Some(max_proposal_attempts) = params.max_proposal_attempts {
+            if !PROPOSAL_ATTEMPTS_LIMITS.contains(&max_proposal_attempts) {
+                return Err(ContractError::ProposalAttemptsLimitsError {});
+            }
+            self.max_proposal_attempts = max_proposal_attempts;
+        }
+
+        if let Some(retry_error_acks) = params.retry_error_acks {
+            self.retry_error_acks = retry_error_acks;
+        }
+
+        if let Some(heartbeat_interval) = params.heartbeat_interval {
+            if heartbeat_interval > MAX_HEARTBEAT_INTERVAL {
+                return Err(ContractError::HeartbeatIntervalError {});
+            }
+            self.heartbeat_interval = heartbeat_interval;
+        }
+
+        if let Some(heartbeat_timeout) = params.heartbeat_timeout {
+            if !TIMEOUT_LIMITS.contains(&heartbeat_timeout) {
+                return Err(ContractError::TimeoutLimitsError {});
+            }
+            self.heartbeat_timeout = heartbeat_timeout;
+        }
+
+        if let Some(max_chunk_size) = params.max_chunk_size {
+            if max_chunk_size != 0 && max_chunk_size < MIN_CHUNK_SIZE {
+                return Err(ContractError::ChunkSizeError {});
+            }
+            self.max_chunk_size = max_chunk_size;
+        }
+
+        if let Some(epoch) = params.epoch {
+            if epoch <= self.epoch {
+                return Err(ContractError::EpochError {
+                    current: self.epoch,
+                });
+            }
+            self.epoch = epoch;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the proposal packet timeout.
+    /// The proposal override takes precedence over the satellite override
+    pub fn proposal_timeout(
+        &self,
+        satellite: &SatelliteInfo,
+        proposal_timeout: Option<u64>,
+    ) -> u64 {
+        proposal_timeout
+            .or(satellite.timeout)
+            .unwrap_or(self.timeout)
+    }
+
+    /// Whether a proposal failed after the given number of attempts can be sent again
+    pub fn can_retry(&self, attempts: u32, timed_out: bool) -> bool {
+        attempts < self.max_proposal_attempts && (timed_out || self.retry_error_acks)
+    }
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores map proposal id -> aggregated proposal status across all target satellites
 pub const PROPOSAL_STATE: Map<u64, ProposalStatus> = Map::new("proposal_state");
 
+/// Stores map (proposal id, channel id) -> packet relayed to the specific satellite
+pub const PROPOSAL_DISPATCHES: Map<(u64, &str), ProposalDispatch> = Map::new("proposal_dispatches");
+
+/// Stores the number of proposals in each aggregated state
+pub const PROPOSAL_COUNTS: Item<StatusCounts> = Item::new("proposal_counts");
+
+/// Stores map channel id -> number of proposal dispatches in each state
+pub const CHANNEL_COUNTS: Map<&str, StatusCounts> = Map::new("channel_counts");
+
+/// Stores set of (aggregated status, proposal id)
+pub const STATUS_PROPOSALS: Map<(&str, u64), ()> = Map::new("status_proposals");
+
+/// Stores set of (channel id, proposal id) for every channel the proposal was sent to
+pub const CHANNEL_PROPOSALS: Map<(&str, u64), ()> = Map::new("channel_proposals");
+
+/// Stores set of (channel id, aggregated status, proposal id)
+pub const CHANNEL_STATUS_PROPOSALS: Map<(&str, &str, u64), ()> =
+    Map::new("channel_status_proposals");
+
+/// Stores map (proposal id, channel id) -> whether the packet timed out for failed dispatches
+/// which can still be retried
+pub const RETRYABLE_DISPATCHES: Map<(u64, &str), bool> = Map::new("retryable_dispatches");
+
+/// Stores map proposal id -> messages relayed to the satellites
+pub const PROPOSAL_MESSAGES: Map<u64, Vec<CosmosMsg>> = Map::new("proposal_messages");
+
+/// Stores map proposal id -> execution mode of the proposal messages
+pub const PROPOSAL_MODES: Map<u64, ExecutionMode> = Map::new("proposal_modes");
+
+/// Stores map (proposal id, channel id, attempt) -> error received from the satellite
+pub const PROPOSAL_ERRORS: Map<(u64, &str, u32), ProposalError> = Map::new("proposal_errors");
+
+/// Error of the latest acknowledged proposal, empty if it was executed successfully
 pub const LAST_ERROR: Item<String> = Item::new("last_error");
 
 /// Contains a proposal to change contract ownership.
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Stores map satellite alias -> satellite info
+pub const SATELLITES: Map<&str, SatelliteInfo> = Map::new("satellites");
+
+/// Stores map channel id -> satellite alias. Ensures one channel serves only one satellite
+pub const SATELLITE_CHANNELS: Map<&str, String> = Map::new("satellite_channels");
+
+/// Stores map channel id -> channel opened by the controller
+pub const CHANNELS: Map<&str, ChannelInfo> = Map::new("channels");
+
+/// Stores map channel id -> the latest reports received from the satellite
+pub const REPORTS: Map<&str, ChannelReports> = Map::new("reports");
+
+/// Stores map channel id -> liveness data of the satellite
+pub const SATELLITE_HEALTH: Map<&str, ChannelHealth> = Map::new("satellite_health");
+
+/// Id of the latest proposal simulation
+pub const SIMULATION_COUNT: Item<u64> = Item::new("simulation_count");
+
+/// Proposal simulations sent to the satellites
+pub const SIMULATIONS: Map<u64, SimulationInfo> = Map::new("simulations");
+
+/// Min number of seconds between two simulations sent to the same satellite by anyone but the owner
+pub const SIMULATION_INTERVAL: u64 = 3600;
+
+/// Stores map channel id -> time of the latest simulation sent by anyone but the owner
+pub const LAST_SIMULATIONS: Map<&str, Timestamp> = Map::new("last_simulations");
+
+/// Satellites are flagged 3 days before they reach the max signal outage by default
+pub const DEFAULT_OUTAGE_WARNING_MARGIN: u64 = 259200;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Loads the satellite by alias and makes sure it can be used to relay packets
+pub fn load_enabled_satellite(
+    storage: &dyn Storage,
+    alias: &str,
+) -> Result<SatelliteInfo, ContractError> {
+    let satellite =
+        SATELLITES
+            .may_load(storage, alias)?
+            .ok_or_else(|| ContractError::SatelliteNotFound {
+                alias: alias.to_string(),
+            })?;
+
+    if !satellite.enabled {
+        return Err(ContractError::SatelliteDisabled {
+            alias: alias.to_string(),
+        });
+    }
+
+    let channel = CHANNELS
+        .may_load(storage, &satellite.channel_id)?
+        .ok_or_else(|| ContractError::ChannelNotConnected {
+            channel_id: satellite.channel_id.clone(),
+        })?;
+    check_satellite_channel(&satellite, &channel)?;
+
+    Ok(satellite)
+}
+
+/// Checks that the satellite port and connection match the channel opened by the controller
+pub fn check_satellite_channel(
+    satellite: &SatelliteInfo,
+    channel: &ChannelInfo,
+) -> Result<(), ContractError> {
+    if satellite.port_id != channel.counterparty_port_id
+        || satellite.connection_id != channel.connection_id
+    {
+        return Err(ContractError::SatelliteChannelMismatch {
+            alias: satellite.alias.clone(),
+            channel_id: channel.channel_id.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the protocol version negotiated for the channel
+pub fn channel_version(storage: &dyn Storage, channel_id: &str) -> StdResult<IbcVersion> {
+    Ok(CHANNELS
+        .may_load(storage, channel_id)?
+        .map(|channel| channel.version)
+        // Channels opened before the versioning was introduced
+        .unwrap_or(IbcVersion::V1))
+}
+
+/// Returns all channels opened by the controller ordered by channel id
+pub fn list_channels(storage: &dyn Storage) -> StdResult<Vec<ChannelInfo>> {
+    CHANNELS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, channel)| channel))
+        .collect()
+}
+
+/// Returns registered satellites ordered by alias
+pub fn list_satellites(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<SatelliteInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    SATELLITES
+        .range(
+            storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, satellite)| satellite))
+        .collect()
+}
+
+/// Returns proposal simulations ordered by id
+pub fn list_simulations(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<SimulationInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    SIMULATIONS
+        .range(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, simulation)| simulation))
+        .collect()
+}
+
+/// Stores the report as the latest one of its kind received from the channel
+pub fn save_report(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    report: SatelliteReport,
+) -> StdResult<()> {
+    let mut reports = REPORTS
+        .may_load(storage, channel_id)?
+        .unwrap_or_else(|| ChannelReports::new(channel_id));
+
+    match report {
+        SatelliteReport::Status(status) => reports.status = Some(status),
+        SatelliteReport::Balances(balances) => reports.balances = Some(balances),
+        SatelliteReport::Config(config) => reports.config = Some(config),
+        // Proposal outcomes are recorded in the proposal dispatches
+        SatelliteReport::ProposalOutcome(_) => return Ok(()),
+    }
+
+    REPORTS.save(storage, channel_id, &reports)
+}
+
+/// Returns the latest reports of every channel ordered by channel id
+pub fn list_reports(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ChannelReports>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    REPORTS
+        .range(
+            storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, reports)| reports))
+        .collect()
+}
+
+/// Returns all registered satellites ordered by alias
+pub fn list_all_satellites(storage: &dyn Storage) -> StdResult<Vec<SatelliteInfo>> {
+    SATELLITES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, satellite)| satellite))
+        .collect()
+}
+
+/// Returns packets relayed to every target satellite of the proposal
+pub fn proposal_dispatches(storage: &dyn Storage, id: u64) -> StdResult<Vec<ProposalDispatch>> {
+    PROPOSAL_DISPATCHES
+        .prefix(id)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, dispatch)| dispatch))
+        .collect()
+}
+
+/// Returns liveness data of the registered satellites ordered by alias
+pub fn satellites_health(
+    storage: &dyn Storage,
+    now: Timestamp,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    warning_margin: u64,
+) -> StdResult<Vec<SatelliteHealthResponse>> {
+    list_satellites(storage, start_after, limit)?
+        .into_iter()
+        .map(|satellite| {
+            let health = SATELLITE_HEALTH
+                .may_load(storage, &satellite.channel_id)?
+                .unwrap_or_default();
+            let outage_deadline = health
+                .last_ack
+                .map(|last_ack| last_ack.plus_seconds(satellite.max_signal_outage));
+            let nearing_outage = match outage_deadline {
+                Some(deadline) => now.plus_seconds(warning_margin) >= deadline,
+                // The satellite has never confirmed it received a packet
+                None => true,
+            };
+
+            Ok(SatelliteHealthResponse {
+                alias: satellite.alias,
+                channel_id: satellite.channel_id,
+                health,
+                outage_deadline,
+                nearing_outage,
+            })
+        })
+        .collect()
+}
+
+/// Records the heartbeat sending time.
+/// Fails if `interval` seconds haven't passed since the previous heartbeat
+pub fn record_heartbeat(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    now: Timestamp,
+    interval: u64,
+) -> Result<(), ContractError> {
+    let mut health = SATELLITE_HEALTH
+        .may_load(storage, channel_id)?
+        .unwrap_or_default();
+
+    if let Some(last_heartbeat_sent) = health.last_heartbeat_sent {
+        if last_heartbeat_sent.plus_seconds(interval) > now {
+            return Err(ContractError::HeartbeatTooEarly {
+                channel_id: channel_id.to_string(),
+            });
+        }
+    }
+
+    health.last_heartbeat_sent = Some(now);
+    SATELLITE_HEALTH.save(storage, channel_id, &health)?;
+
+    Ok(())
+}
+
+/// Updates the satellite liveness data with the packet outcome
+pub fn record_packet_outcome(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    outcome: &PacketOutcome,
+    time: Timestamp,
+) -> StdResult<()> {
+    SATELLITE_HEALTH.update(storage, channel_id, |health| -> StdResult<_> {
+        let mut health = health.unwrap_or_default();
+        match outcome {
+            PacketOutcome::Executed(_) => {
+                health.last_ack = Some(time);
+                health.consecutive_timeouts = 0;
+            }
+            // The satellite received the packet, although it didn't succeed
+            PacketOutcome::Failed(err) => {
+                health.last_error = Some(err.error.clone());
+                health.consecutive_timeouts = 0;
+            }
+            PacketOutcome::TimedOut => health.consecutive_timeouts += 1,
+        }
+        Ok(health)
+    })?;
+
+    Ok(())
+}
+
+/// Returns the full proposal dispatch record
+pub fn load_proposal(storage: &dyn Storage, id: u64) -> StdResult<ProposalResponse> {
+    Ok(ProposalResponse {
+        id,
+        status: PROPOSAL_STATE.load(storage, id)?,
+        // Proposals relayed before v1.2.0 have no stored messages
+        messages: PROPOSAL_MESSAGES.may_load(storage, id)?.unwrap_or_default(),
+        mode: PROPOSAL_MODES.may_load(storage, id)?.unwrap_or_default(),
+        dispatches: proposal_dispatches(storage, id)?,
+    })
+}
+
+/// Saves the aggregated proposal status keeping the status counters and indexes in sync
+pub fn save_proposal_status(
+    storage: &mut dyn Storage,
+    id: u64,
+    status: &ProposalStatus,
+) -> StdResult<()> {
+    let channels = PROPOSAL_DISPATCHES
+        .prefix(id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut counts = PROPOSAL_COUNTS.may_load(storage)?.unwrap_or_default();
+
+    if let Some(old_status) = PROPOSAL_STATE.may_load(storage, id)? {
+        let old_status_key = old_status.to_string();
+        counts.remove(&old_status);
+        STATUS_PROPOSALS.remove(storage, (&old_status_key, id));
+        for channel_id in &channels {
+            CHANNEL_STATUS_PROPOSALS.remove(storage, (channel_id, &old_status_key, id));
+        }
+    }
+
+    let status_key = status.to_string();
+    counts.add(status);
+    STATUS_PROPOSALS.save(storage, (&status_key, id), &())?;
+    for channel_id in &channels {
+        CHANNEL_STATUS_PROPOSALS.save(storage, (channel_id, &status_key, id), &())?;
+    }
+
+    PROPOSAL_COUNTS.save(storage, &counts)?;
+    PROPOSAL_STATE.save(storage, id, status)
+}
+
+/// Saves the proposal dispatch keeping the per channel counters and indexes in sync.
+/// The dispatch must be saved before the aggregated status of a new proposal
+pub fn save_dispatch(
+    storage: &mut dyn Storage,
+    id: u64,
+    dispatch: &ProposalDispatch,
+) -> StdResult<()> {
+    let channel_id = dispatch.channel_id.as_str();
+    let old_status = PROPOSAL_DISPATCHES
+        .may_load(storage, (id, channel_id))?
+        .map(|dispatch| dispatch.status);
+
+    if old_status.as_ref() != Some(&dispatch.status) {
+        let mut counts = CHANNEL_COUNTS
+            .may_load(storage, channel_id)?
+            .unwrap_or_default();
+        if let Some(old_status) = &old_status {
+            counts.remove(old_status);
+        }
+        counts.add(&dispatch.status);
+        CHANNEL_COUNTS.save(storage, channel_id, &counts)?;
+    }
+
+    CHANNEL_PROPOSALS.save(storage, (channel_id, id), &())?;
+    PROPOSAL_DISPATCHES.save(storage, (id, channel_id), dispatch)
+}
+
+/// Returns proposals ordered by id and filtered by the aggregated state and the target channel
+pub fn list_proposals(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    status: Option<ProposalStatus>,
+    channel: Option<String>,
+) -> StdResult<Vec<ProposalResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let status = status.map(|status| status.to_string());
+
+    let ids: Box<dyn Iterator<Item = StdResult<u64>>> =
+        match (&status, &channel) {
+            (None, None) => PROPOSAL_STATE.keys(storage, start, None, Order::Ascending),
+            (Some(status), None) => {
+                STATUS_PROPOSALS
+                    .prefix(status)
+                    .keys(storage, start, None, Order::Ascending)
+            }
+            (None, Some(channel)) => {
+                CHANNEL_PROPOSALS
+                    .prefix(channel)
+                    .keys(storage, start, None, Order::Ascending)
+            }
+            (Some(status), Some(channel)) => CHANNEL_STATUS_PROPOSALS
+                .prefix((channel, status))
+                .keys(storage, start, None, Order::Ascending),
+        };
+
+    ids.take(limit)
+        .map(|id| id.and_then(|id| load_proposal(storage, id)))
+        .collect()
+}
+
+/// Returns the number of proposals in each state overall and per satellite channel
+pub fn proposal_counts(storage: &dyn Storage) -> StdResult<ProposalCountsResponse> {
+    Ok(ProposalCountsResponse {
+        total: PROPOSAL_COUNTS.may_load(storage)?.unwrap_or_default(),
+        channels: CHANNEL_COUNTS
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(channel_id, counts)| ChannelStatusCounts { channel_id, counts }))
+            .collect::<StdResult<_>>()?,
+    })
+}
+
+/// Returns the proposal state on every target satellite channel
+pub fn proposal_channels(storage: &dyn Storage, id: u64) -> StdResult<Vec<ChannelProposalStatus>> {
+    Ok(proposal_dispatches(storage, id)?
+        .into_iter()
+        .map(|dispatch| ChannelProposalStatus {
+            channel_id: dispatch.channel_id,
+            status: dispatch.status,
+        })
+        .collect())
+}
+
+/// Describes the result of the proposal packet relayed to a satellite
+pub enum PacketOutcome {
+    /// The satellite receipt is missing for v1 channels and timelocked proposals
+    Executed(Option<ExecutionReceipt>),
+    Failed(AckError),
+    TimedOut,
+}
+
+/// Records the proposal outcome on the given channel.
+/// Returns the aggregated proposal status once every target satellite has responded
+/// and there is nothing left to retry, otherwise returns None.
+pub fn process_proposal_outcome(
+    storage: &mut dyn Storage,
+    id: u64,
+    channel_id: &str,
+    outcome: PacketOutcome,
+    time: Timestamp,
+) -> StdResult<Option<ProposalStatus>> {
+    let config = CONFIG.load(storage)?;
+
+    let (code, message_index) = match &outcome {
+        PacketOutcome::Failed(err) => (Some(err.code.clone()), err.message_index),
+        _ => (None, None),
+    };
+
+    match &outcome {
+        PacketOutcome::Executed(_) => LAST_ERROR.save(storage, &String::new())?,
+        PacketOutcome::Failed(err) => LAST_ERROR.save(storage, &err.error)?,
+        PacketOutcome::TimedOut => {}
+    }
+
+    let timed_out = matches!(outcome, PacketOutcome::TimedOut);
+    let mut dispatch = match PROPOSAL_DISPATCHES.may_load(storage, (id, channel_id))? {
+        // Proposals sent before v1.2.0 are tracked by the aggregated state only
+        None => return process_legacy_outcome(storage, id, channel_id, outcome, time),
+        Some(dispatch) if dispatch.status == ProposalStatus::InProgress => dispatch,
+        Some(dispatch) => {
+            return Err(StdError::generic_err(format!(
+                "Proposal id: {} state is already {}",
+                id, dispatch.status
+            )))
+        }
+    };
+
+    match outcome {
+        PacketOutcome::Executed(receipt) => {
+            dispatch.status = ProposalStatus::Executed;
+            dispatch.receipt = receipt;
+        }
+        PacketOutcome::Failed(err) => {
+            dispatch.status = ProposalStatus::Failed;
+            dispatch.error = Some(err.error);
+            dispatch.retryable = config.can_retry(dispatch.attempts, false);
+        }
+        PacketOutcome::TimedOut => {
+            dispatch.status = ProposalStatus::Failed;
+            dispatch.error = Some("Packet timed out".to_string());
+            dispatch.retryable = config.can_retry(dispatch.attempts, true);
+        }
+    }
+    dispatch.finished_at = Some(time);
+    save_dispatch(storage, id, &dispatch)?;
+    if dispatch.retryable {
+        RETRYABLE_DISPATCHES.save(storage, (id, channel_id), &timed_out)?;
+    }
+
+    if let Some(error) = dispatch.error {
+        PROPOSAL_ERRORS.save(
+            storage,
+            (id, channel_id, dispatch.attempts),
+            &ProposalError {
+                proposal_id: id,
+                channel_id: channel_id.to_string(),
+                attempt: dispatch.attempts,
+                time,
+                error,
+                code,
+                message_index,
+            },
+        )?;
+    }
+
+    finalize_proposal(storage, id)
+}
+
+/// Saves the aggregated proposal status once every target satellite has responded
+/// and there is nothing left to retry, otherwise returns None
+fn finalize_proposal(storage: &mut dyn Storage, id: u64) -> StdResult<Option<ProposalStatus>> {
+    let dispatches = proposal_dispatches(storage, id)?;
+    if dispatches
+        .iter()
+        .any(|dispatch| dispatch.status == ProposalStatus::InProgress || dispatch.retryable)
+    {
+        return Ok(None);
+    }
+
+    let status = if dispatches
+        .iter()
+        .all(|dispatch| dispatch.status == ProposalStatus::Executed)
+    {
+        ProposalStatus::Executed
+    } else {
+        ProposalStatus::Failed
+    };
+    save_proposal_status(storage, id, &status)?;
+
+    Ok(Some(status))
+}
+
+/// Marks the failed dispatch as not retryable anymore.
+/// Returns the aggregated proposal status if the proposal is complete as a result
+pub fn abandon_dispatch(
+    storage: &mut dyn Storage,
+    id: u64,
+    channel_id: &str,
+) -> StdResult<Option<ProposalStatus>> {
+    RETRYABLE_DISPATCHES.remove(storage, (id, channel_id));
+    PROPOSAL_DISPATCHES.update(storage, (id, channel_id), |dispatch| {
+        let mut dispatch = dispatch.ok_or_else(|| StdError::not_found("Proposal dispatch"))?;
+        dispatch.retryable = false;
+        Ok::<_, StdError>(dispatch)
+    })?;
+
+    finalize_proposal(storage, id)
+}
+
+/// Abandons every retryable dispatch matching the predicate which receives the channel id,
+/// the number of attempts and whether the packet timed out.
+/// Returns the proposals completed as a result along with their aggregated status
+pub fn abandon_dispatches(
+    storage: &mut dyn Storage,
+    predicate: impl Fn(&str, u32, bool) -> bool,
+) -> StdResult<Vec<(u64, ProposalStatus)>> {
+    let candidates = RETRYABLE_DISPATCHES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut completed = vec![];
+    for ((id, channel_id), timed_out) in candidates {
+        let attempts = PROPOSAL_DISPATCHES
+            .load(storage, (id, &channel_id))?
+            .attempts;
+        if predicate(&channel_id, attempts, timed_out) {
+            if let Some(status) = abandon_dispatch(storage, id, &channel_id)? {
+                completed.push((id, status));
+            }
+        }
+    }
+
+    Ok(completed)
+}
+
+/// Records the outcome of the proposal sent before v1.2.0 which has no dispatch record.
+/// Such proposals were sent to a single channel, thus the outcome is final right away
+fn process_legacy_outcome(
+    storage: &mut dyn Storage,
+    id: u64,
+    channel_id: &str,
+    outcome: PacketOutcome,
+    time: Timestamp,
+) -> StdResult<Option<ProposalStatus>> {
+    match PROPOSAL_STATE.may_load(storage, id)? {
+        Some(ProposalStatus::InProgress) => {}
+        Some(status) => {
+            return Err(StdError::generic_err(format!(
+                "Proposal id: {id} state is already {status}"
+            )))
+        }
+        None => {
+            return Err(StdError::generic_err(format!(
+                "Proposal {id} was not executed via controller"
+            )))
+        }
+    }
+
+    let (status, error, code, message_index) = match outcome {
+        PacketOutcome::Executed(_) => (ProposalStatus::Executed, None, None, None),
+        PacketOutcome::Failed(err) => (
+            ProposalStatus::Failed,
+            Some(err.error),
+            Some(err.code),
+            err.message_index,
+        ),
+        PacketOutcome::TimedOut => (
+            ProposalStatus::Failed,
+            Some("Packet timed out".to_string()),
+            None,
+            None,
+        ),
+    };
+    save_proposal_status(storage, id, &status)?;
+
+    if let Some(error) = error {
+        PROPOSAL_ERRORS.save(
+            storage,
+            (id, channel_id, 1),
+            &ProposalError {
+                proposal_id: id,
+                channel_id: channel_id.to_string(),
+                attempt: 1,
+                time,
+                error,
+                code,
+                message_index,
+            },
+        )?;
+    }
+
+    Ok(Some(status))
+}
+
+/// Returns all errors which occurred while relaying the proposal
+pub fn proposal_errors(storage: &dyn Storage, id: u64) -> StdResult<Vec<ProposalError>> {
+    PROPOSAL_ERRORS
+        .sub_prefix(id)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, error)| error))
+        .collect()
+}
+
+/// Returns errors of all proposals ordered by (proposal id, channel id, attempt)
+pub fn list_errors(
+    storage: &dyn Storage,
+    start_after: Option<(u64, String, u32)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ProposalError>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    PROPOSAL_ERRORS
+        .range(
+            storage,
+            start_after.as_ref().map(|(id, channel_id, attempt)| {
+                Bound::exclusive((*id, channel_id.as_str(), *attempt))
+            }),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, error)| error))
+        .collect()
+}
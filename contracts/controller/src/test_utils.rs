@@ -1,9 +1,14 @@
-use crate::contract::instantiate;
+use astroport_ibc::{IbcVersion, SIGNAL_OUTAGE_LIMITS};
+
+use crate::contract::{execute, instantiate};
+use crate::ibc::{ibc_channel_connect, IBC_ORDERING};
+use crate::state::CHANNELS;
 use cosmwasm_std::testing::{
-    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    mock_dependencies, mock_env, mock_ibc_channel_connect_ack, mock_info, MockApi, MockQuerier,
+    MockStorage,
 };
 use cosmwasm_std::{Env, MessageInfo, OwnedDeps};
-use ibc_controller_package::InstantiateMsg;
+use ibc_controller_package::{ExecuteMsg, InstantiateMsg};
 
 pub const OWNER: &str = "owner";
 
@@ -36,3 +41,41 @@ pub fn init_contract(
     )
     .unwrap();
 }
+
+pub fn connect_channel(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: Env,
+    channel_id: &str,
+) {
+    ibc_channel_connect(
+        deps.as_mut(),
+        env,
+        mock_ibc_channel_connect_ack(channel_id, IBC_ORDERING, IbcVersion::LATEST.as_str()),
+    )
+    .unwrap();
+}
+
+/// Registers the satellite and connects its channel
+pub fn register_satellite(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: Env,
+    alias: &str,
+    channel_id: &str,
+) {
+    connect_channel(deps, env.clone(), channel_id);
+    let channel = CHANNELS.load(deps.as_ref().storage, channel_id).unwrap();
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RegisterSatellite {
+            alias: alias.to_string(),
+            channel_id: channel_id.to_string(),
+            port_id: channel.counterparty_port_id,
+            connection_id: channel.connection_id,
+            max_signal_outage: *SIGNAL_OUTAGE_LIMITS.start(),
+            timeout: None,
+        },
+    )
+    .unwrap();
+}
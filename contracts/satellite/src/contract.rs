@@ -1,25 +1,53 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, to_json_binary, wasm_execute, Api, Binary, CosmosMsg, CustomMsg, Deps, DepsMut,
-    Empty, Env, IbcMsg, IbcTimeout, MessageInfo, QuerierWrapper, Reply, Response, StdError,
-    WasmMsg,
+    attr, coin, ensure_eq, from_json, to_json_binary, wasm_execute, Api, Binary, CosmosMsg,
+    CustomMsg, Deps, DepsMut, Empty, Env, IbcMsg, IbcTimeout, MessageInfo, Order, Reply, Response,
+    StdError, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use cw_utils::parse_execute_response_data;
 
-use astro_satellite_package::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use astro_satellite_package::{
+    AckError, AckErrorCode, BalanceReport, BatchProposalResult, ExecuteMsg, ExecutionMode,
+    ExecutionReceipt, IbcAckResult, IbcVersion, InstantiateMsg, LastHubSignalResponse,
+    MessageResult, OwnershipProposalResponse, ProposalOutcomeReport, QueryMsg, QueueStatus,
+    QueuedProposal, ReportKind, SatelliteReport, SimulationResult, StatusReport,
+};
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 
 use crate::error::ContractError;
+use crate::ibc::ack_batch;
+use crate::migration::migrate_config;
+use crate::policy::{check_policy, load_policy, update_message_policy};
+use crate::stargate::{decode_stargate, ibc_transfer_with_memo, MAX_NESTING_DEPTH};
 use crate::state::{
-    instantiate_state, set_emergency_owner_as_admin, store_proposal, update_config, CONFIG,
-    OWNERSHIP_PROPOSAL, REPLY_DATA, RESULTS,
+    channel_version, clear_upload, instantiate_state, last_executed_proposal,
+    set_emergency_owner_as_admin, store_proposal, update_config, Config, ExecutionProgress,
+    FailureProbe, BATCH_PENDING, BATCH_RESULTS, CONFIG, CONTROLLER_EPOCH, EXECUTION_PROGRESS,
+    FAILURE_PROBE, LAST_ASTRO_TRANSFER, LAST_REPORTS, LATEST_HUB_SIGNAL_TIME, MESSAGE_RESULTS,
+    OWNERSHIP_PROPOSAL, PENDING_PROPOSAL, QUEUED_PROPOSALS, REPLY_DATA, REPORT_INTERVAL, RESULTS,
+    UPLOADS,
 };
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const RECEIVE_ID: u64 = 1;
+pub const EXECUTE_QUEUED_ID: u64 = 2;
+pub const EXECUTE_BATCHED_ID: u64 = 3;
+pub const SIMULATION_ID: u64 = 4;
+pub const SIMULATION_STEP_ID: u64 = 5;
+pub const EXECUTE_PROPOSAL_ID: u64 = 6;
+pub const PROBE_ID: u64 = 7;
+
+/// Default pagination limit
+const DEFAULT_LIMIT: u32 = 10;
+/// Maximum pagination limit
+const MAX_LIMIT: u32 = 30;
+/// Max number of proposal messages the failing message is searched among
+pub const MAX_PROBED_MESSAGES: usize = 64;
 
 #[cfg_attr(all(not(feature = "library")), entry_point)]
 pub fn instantiate(
@@ -36,17 +64,168 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
-    let proposal_id = REPLY_DATA.load(deps.storage)?;
+pub fn reply(mut deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
         RECEIVE_ID => {
-            store_proposal(deps, env, proposal_id)?;
-            Ok(Response::new())
+            let proposal_id = REPLY_DATA.load(deps.storage)?;
+            // Proposals relayed over v1 channels reply on the last message only
+            let Some(mut progress) = EXECUTION_PROGRESS.may_load(deps.storage)? else {
+                store_proposal(deps, env, proposal_id)?;
+                return Ok(Response::new());
+            };
+
+            // Only messages of best-effort proposals reply on failures
+            let result = match reply.result {
+                SubMsgResult::Ok(response) => MessageResult::new(response)?,
+                SubMsgResult::Err(error) => MessageResult::failed(error),
+            };
+            progress.results.push(result);
+            if progress.results.len() < progress.total as usize {
+                EXECUTION_PROGRESS.save(deps.storage, &progress)?;
+                return Ok(Response::new());
+            }
+
+            EXECUTION_PROGRESS.remove(deps.storage);
+            store_proposal(deps.branch(), env.clone(), proposal_id)?;
+
+            let mut response = Response::new();
+            if progress.mode == ExecutionMode::BestEffort {
+                for (index, result) in progress.results.iter().enumerate() {
+                    MESSAGE_RESULTS.save(deps.storage, (proposal_id, index as u32), result)?;
+                }
+            }
+
+            // The data of the last reply overrides the data of the execution
+            let receipt = ExecutionReceipt::new(&env.block, progress.results);
+            let failed = receipt.failed_messages();
+            if !failed.is_empty() {
+                response = response.add_attribute(
+                    "failed_messages",
+                    failed
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+            }
+            Ok(response.set_data(to_json_binary(&receipt)?))
+        }
+        EXECUTE_PROPOSAL_ID => {
+            let proposal = PENDING_PROPOSAL.load(deps.storage)?;
+            PENDING_PROPOSAL.remove(deps.storage);
+
+            match reply.result {
+                // The data of the reply overrides the packet acknowledgement
+                SubMsgResult::Ok(SubMsgResponse { data, .. }) => {
+                    Ok(Response::new().set_data(to_json_binary(&IbcAckResult::Ok(
+                        execution_data(data).unwrap_or_else(|| b"ok".into()),
+                    ))?))
+                }
+                SubMsgResult::Err(error) => probe_failure(
+                    deps.storage,
+                    &env,
+                    FailureProbe {
+                        id: proposal.id,
+                        failed: proposal.messages.len() as u32,
+                        messages: proposal.messages,
+                        passed: 0,
+                        error,
+                    },
+                ),
+            }
+        }
+        PROBE_ID => {
+            let mut probe = FAILURE_PROBE.load(deps.storage)?;
+            FAILURE_PROBE.remove(deps.storage);
+
+            match reply.result {
+                // The simulation fails only if the probed messages passed
+                SubMsgResult::Err(_) => probe.passed = probe.prefix_len(),
+                SubMsgResult::Ok(_) => probe.failed = probe.prefix_len(),
+            }
+            probe_failure(deps.storage, &env, probe)
+        }
+        EXECUTE_QUEUED_ID => {
+            let proposal_id = REPLY_DATA.load(deps.storage)?;
+            let status = match reply.result {
+                SubMsgResult::Ok(_) => {
+                    store_proposal(deps.branch(), env.clone(), proposal_id)?;
+                    QueueStatus::Executed
+                }
+                SubMsgResult::Err(error) => QueueStatus::Failed { error },
+            };
+            finalize_queued(deps.storage, &env, proposal_id, status)
+        }
+        SIMULATION_STEP_ID => match reply.result {
+            // Revert the messages which passed
+            SubMsgResult::Ok(_) => Err(ContractError::MessagesCheckPassed {}),
+            // The failed messages were already reverted
+            SubMsgResult::Err(error) => {
+                Ok(Response::new().set_data(to_json_binary(&SimulationResult::Failed { error })?))
+            }
+        },
+        SIMULATION_ID => {
+            // The simulation step errors only if the messages passed
+            let result = match reply.result {
+                SubMsgResult::Ok(SubMsgResponse { data, .. }) => {
+                    execution_data(data).and_then(|data| from_json(data).ok())
+                }
+                SubMsgResult::Err(_) => Some(SimulationResult::Passed {}),
+            };
+
+            // The data of the reply overrides the packet acknowledgement.
+            // Failing here would abort the whole packet receive, so a missing result is acked
+            let ack = match result {
+                Some(result) => IbcAckResult::Ok(to_json_binary(&result)?),
+                None => IbcAckResult::Error(
+                    AckError::new(AckErrorCode::Unknown, "Simulation result is missing")
+                        .encode()?,
+                ),
+            };
+            Ok(Response::new().set_data(to_json_binary(&ack)?))
+        }
+        EXECUTE_BATCHED_ID => {
+            // Batched proposals are replied in the order they were executed
+            let mut pending = BATCH_PENDING.load(deps.storage)?;
+            let proposal_id = pending.remove(0);
+            let result = match reply.result {
+                SubMsgResult::Ok(SubMsgResponse { data, .. }) => {
+                    store_proposal(deps.branch(), env, proposal_id)?;
+                    IbcAckResult::Ok(execution_data(data).unwrap_or_else(|| b"ok".into()))
+                }
+                SubMsgResult::Err(error) => {
+                    IbcAckResult::Error(AckError::new(AckErrorCode::MessageFailed, error).encode()?)
+                }
+            };
+
+            let mut results = BATCH_RESULTS.load(deps.storage)?;
+            results.push(BatchProposalResult {
+                id: proposal_id,
+                result,
+            });
+
+            if pending.is_empty() {
+                BATCH_PENDING.remove(deps.storage);
+                BATCH_RESULTS.remove(deps.storage);
+                // The data of the last reply overrides the packet acknowledgement
+                Ok(Response::new().set_data(ack_batch(&results)?))
+            } else {
+                BATCH_PENDING.save(deps.storage, &pending)?;
+                BATCH_RESULTS.save(deps.storage, &results)?;
+                Ok(Response::new())
+            }
         }
         _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
+/// Returns the data set by the contract executed in the submessage.
+/// The chain wraps it into `MsgExecuteContractResponse` before passing it to the reply
+fn execution_data(data: Option<Binary>) -> Option<Binary> {
+    data.and_then(|data| parse_execute_response_data(&data).ok())
+        .and_then(|response| response.data)
+}
+
 #[cfg_attr(all(not(feature = "library")), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -55,32 +234,16 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::TransferAstro {} => {
-            let config = CONFIG.load(deps.storage)?;
-
-            // Query and send the whole astro balance
-            let astro_balance = deps
-                .querier
-                .query_balance(&env.contract.address, &config.astro_denom)?;
-
-            if astro_balance.amount.is_zero() {
-                return Err(ContractError::NoAstroBalance {});
-            }
-
-            let msg = CosmosMsg::Ibc(IbcMsg::Transfer {
-                channel_id: config.transfer_channel,
-                to_address: config.main_maker,
-                amount: astro_balance,
-                timeout: IbcTimeout::from(env.block.time.plus_seconds(config.timeout)),
-            });
-            Ok(Response::new()
-                .add_message(msg)
-                .add_attribute("action", "transfer_astro"))
-        }
+        ExecuteMsg::TransferAstro {
+            amount,
+            memo,
+            receiver,
+        } => transfer_astro(deps, env, info, amount, memo, receiver),
         ExecuteMsg::UpdateConfig(params) => update_config(deps, info, env, params),
-        ExecuteMsg::CheckMessages(messages) => check_messages(info, deps.api, env, messages),
+        ExecuteMsg::UpdateMessagePolicy(policy) => update_message_policy(deps, info, policy),
+        ExecuteMsg::CheckMessages(messages) => check_messages(deps.as_ref(), info, env, messages),
         ExecuteMsg::ExecuteFromMultisig(proposal_messages) => {
-            exec_from_multisig(deps.querier, info, env, proposal_messages)
+            exec_from_multisig(deps.as_ref(), info, env, proposal_messages)
         }
         ExecuteMsg::CheckMessagesPassed {} => Err(ContractError::MessagesCheckPassed {}),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
@@ -115,13 +278,459 @@ pub fn execute(
             .map_err(Into::into)
         }
         ExecuteMsg::SetEmergencyOwnerAsAdmin {} => set_emergency_owner_as_admin(deps, env, info),
+        ExecuteMsg::SendReport { kind } => send_report(deps, env, info, kind),
+        ExecuteMsg::ExecuteQueued { id } => execute_queued(deps, env, id),
+        ExecuteMsg::VetoQueued { id } => veto_queued(deps, env, info, id),
+        ExecuteMsg::RunQueued { id } => {
+            ensure_eq!(
+                info.sender,
+                env.contract.address,
+                ContractError::Unauthorized {}
+            );
+
+            let proposal = QUEUED_PROPOSALS.load(deps.storage, id)?;
+            let response = Response::new()
+                .add_attribute("action", "run_queued")
+                .add_attribute("proposal_id", id.to_string());
+
+            match proposal.mode {
+                ExecutionMode::Atomic => Ok(response.add_messages(proposal.messages)),
+                // Failed messages are recorded without failing the queued proposal
+                ExecutionMode::BestEffort if !proposal.messages.is_empty() => Ok(response
+                    .add_submessages(execute_with_receipt(
+                        deps.storage,
+                        id,
+                        proposal.messages,
+                        proposal.mode,
+                    )?)),
+                ExecutionMode::BestEffort => Ok(response),
+            }
+        }
+        ExecuteMsg::ClearExpiredUpload { id } => {
+            let upload = UPLOADS.load(deps.storage, id)?;
+            if upload.expires_at >= env.block.time {
+                return Err(ContractError::UploadNotExpired {
+                    id,
+                    expires_at: upload.expires_at,
+                });
+            }
+            clear_upload(deps.storage, id)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "clear_expired_upload")
+                .add_attribute("proposal_id", id.to_string()))
+        }
+        ExecuteMsg::RunSimulation { id, messages } => {
+            ensure_eq!(
+                info.sender,
+                env.contract.address,
+                ContractError::Unauthorized {}
+            );
+
+            Ok(Response::new()
+                .add_attribute("action", "run_simulation")
+                .add_attribute("simulation_id", id.to_string())
+                .add_submessage(SubMsg::reply_always(
+                    wasm_execute(
+                        &env.contract.address,
+                        &ExecuteMsg::<Empty>::RunBatched {
+                            id,
+                            messages,
+                            mode: ExecutionMode::Atomic,
+                        },
+                        vec![],
+                    )?,
+                    SIMULATION_STEP_ID,
+                )))
+        }
+        ExecuteMsg::RunBatched { id, messages, mode } => {
+            ensure_eq!(
+                info.sender,
+                env.contract.address,
+                ContractError::Unauthorized {}
+            );
+
+            let response = Response::new()
+                .add_attribute("action", "run_batched")
+                .add_attribute("proposal_id", id.to_string());
+
+            // The receipt is returned as the data of the execution
+            if messages.is_empty() {
+                Ok(response.set_data(to_json_binary(&ExecutionReceipt::new(&env.block, vec![]))?))
+            } else {
+                Ok(response.add_submessages(execute_with_receipt(
+                    deps.storage,
+                    id,
+                    messages,
+                    mode,
+                )?))
+            }
+        }
+    }
+}
+
+/// Executes the proposal messages in submessages collecting the result of each of them.
+/// The execution receipt is returned by the reply of the last message.
+/// Failures of best-effort proposal messages are recorded instead of reverting the proposal
+fn execute_with_receipt(
+    storage: &mut dyn Storage,
+    id: u64,
+    messages: Vec<CosmosMsg>,
+    mode: ExecutionMode,
+) -> StdResult<Vec<SubMsg>> {
+    REPLY_DATA.save(storage, &id)?;
+    EXECUTION_PROGRESS.save(
+        storage,
+        &ExecutionProgress {
+            total: messages.len() as u32,
+            results: vec![],
+            mode: mode.clone(),
+        },
+    )?;
+
+    Ok(messages
+        .into_iter()
+        .map(|msg| match mode {
+            ExecutionMode::Atomic => SubMsg::reply_on_success(msg, RECEIVE_ID),
+            ExecutionMode::BestEffort => SubMsg::reply_always(msg, RECEIVE_ID),
+        })
+        .collect())
+}
+
+/// Simulates the next prefix of the failed proposal to find out whether it passes.
+/// Submessage errors are redacted by the chain, thus the failing message can be located
+/// only by bisecting the proposal. Proposals with more than [`MAX_PROBED_MESSAGES`] messages
+/// are acknowledged without the index of the failing message
+fn probe_failure(
+    storage: &mut dyn Storage,
+    env: &Env,
+    probe: FailureProbe,
+) -> Result<Response, ContractError> {
+    if probe.messages.len() > MAX_PROBED_MESSAGES {
+        let error = AckError::new(AckErrorCode::MessageFailed, probe.error);
+        return Ok(Response::new()
+            .add_attribute("proposal_id", probe.id.to_string())
+            .set_data(to_json_binary(&IbcAckResult::Error(error.encode()?))?));
+    }
+
+    // The failing message is found once the passing and the failing prefixes differ by one message
+    if probe.failed <= probe.passed + 1 {
+        return ack_failed_message(probe);
+    }
+
+    let simulate_msg = wasm_execute(
+        &env.contract.address,
+        &ExecuteMsg::<Empty>::RunSimulation {
+            id: probe.id,
+            messages: probe.messages[..probe.prefix_len() as usize].to_vec(),
+        },
+        vec![],
+    )?;
+    FAILURE_PROBE.save(storage, &probe)?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_always(simulate_msg, PROBE_ID)))
+}
+
+/// Overrides the packet acknowledgement with the index of the failing proposal message
+fn ack_failed_message(probe: FailureProbe) -> Result<Response, ContractError> {
+    let index = probe.passed;
+    let error = AckError::message_failed(index, probe.error);
+
+    Ok(Response::new()
+        .add_attribute("proposal_id", probe.id.to_string())
+        .add_attribute("failed_message", index.to_string())
+        .set_data(to_json_binary(&IbcAckResult::Error(error.encode()?))?))
+}
+
+/// Loads the proposal which is still waiting in the timelock queue
+fn load_queued(storage: &dyn Storage, id: u64) -> Result<QueuedProposal, ContractError> {
+    QUEUED_PROPOSALS
+        .may_load(storage, id)?
+        .filter(|proposal| proposal.status == QueueStatus::Queued)
+        .ok_or(ContractError::ProposalNotQueued { id })
+}
+
+/// Executes the queued proposal once its timelock has expired.
+/// Messages are executed in a submessage, so that a failure is recorded instead of reverted
+pub fn execute_queued(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let proposal = load_queued(deps.storage, id)?;
+    if env.block.time < proposal.eta {
+        return Err(ContractError::TimelockNotExpired {
+            id,
+            eta: proposal.eta,
+        });
+    }
+
+    REPLY_DATA.save(deps.storage, &id)?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_always(
+            wasm_execute(
+                &env.contract.address,
+                &ExecuteMsg::<Empty>::RunQueued { id },
+                vec![],
+            )?,
+            EXECUTE_QUEUED_ID,
+        ))
+        .add_attribute("action", "execute_queued")
+        .add_attribute("proposal_id", id.to_string()))
+}
+
+/// Cancels the queued proposal while its timelock hasn't expired yet.
+/// Only the guardian or the emergency owner can veto proposals
+pub fn veto_queued(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.emergency_owner && Some(&info.sender) != config.guardian.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let proposal = load_queued(deps.storage, id)?;
+    if env.block.time >= proposal.eta {
+        return Err(ContractError::VetoPeriodExpired {
+            id,
+            eta: proposal.eta,
+        });
+    }
+
+    Ok(
+        finalize_queued(deps.storage, &env, id, QueueStatus::Vetoed)?
+            .add_attribute("action", "veto_queued"),
+    )
+}
+
+/// Saves the final status of the queued proposal and reports it to the controller
+/// if the governance channel supports reports
+fn finalize_queued(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+    status: QueueStatus,
+) -> Result<Response, ContractError> {
+    let mut proposal = QUEUED_PROPOSALS.load(storage, id)?;
+    proposal.status = status.clone();
+    QUEUED_PROPOSALS.save(storage, id, &proposal)?;
+
+    let mut response = Response::new()
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("status", status.to_string());
+
+    let config = CONFIG.load(storage)?;
+    if let Some(msg) = outcome_report_msg(storage, env, &config, id, status)? {
+        response = response.add_message(msg);
+    }
+
+    Ok(response)
+}
+
+/// Builds the packet reporting the final status of the queued proposal.
+/// Returns None if the governance channel doesn't support reports
+fn outcome_report_msg(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    id: u64,
+    status: QueueStatus,
+) -> StdResult<Option<IbcMsg>> {
+    let Some(gov_channel) = config.gov_channel.clone() else {
+        return Ok(None);
+    };
+    if channel_version(storage, &gov_channel)? < IbcVersion::V2 {
+        return Ok(None);
+    }
+
+    let report = SatelliteReport::ProposalOutcome(ProposalOutcomeReport {
+        time: env.block.time,
+        id,
+        status,
+    });
+
+    Ok(Some(IbcMsg::SendPacket {
+        channel_id: gov_channel,
+        data: to_json_binary(&report)?,
+        timeout: IbcTimeout::from(env.block.time.plus_seconds(config.timeout)),
+    }))
+}
+
+/// Sends the report to the controller over the governance channel.
+/// Reports of the same kind are rate limited unless sent by the owner
+pub fn send_report(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    kind: ReportKind,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let gov_channel = config
+        .gov_channel
+        .clone()
+        .ok_or(ContractError::GovernanceChannelNotFound {})?;
+
+    let version = channel_version(deps.storage, &gov_channel)?;
+    if version < IbcVersion::V2 {
+        return Err(ContractError::UnsupportedPacket { version });
+    }
+
+    let kind_key = kind.to_string();
+    if info.sender != config.owner {
+        if let Some(last_report) = LAST_REPORTS.may_load(deps.storage, &kind_key)? {
+            let next_report = last_report.plus_seconds(REPORT_INTERVAL);
+            if env.block.time < next_report {
+                return Err(ContractError::ReportTooEarly {
+                    kind: kind_key,
+                    next_report,
+                });
+            }
+        }
+    }
+    LAST_REPORTS.save(deps.storage, &kind_key, &env.block.time)?;
+
+    let report = match kind {
+        ReportKind::Status => SatelliteReport::Status(StatusReport {
+            time: env.block.time,
+            version: get_contract_version(deps.storage)?.version,
+            latest_hub_signal_time: LATEST_HUB_SIGNAL_TIME.load(deps.storage)?,
+            last_executed_proposal: last_executed_proposal(deps.storage)?,
+        }),
+        ReportKind::Balances => SatelliteReport::Balances(BalanceReport {
+            time: env.block.time,
+            balances: deps.querier.query_all_balances(&env.contract.address)?,
+        }),
+        ReportKind::Config => SatelliteReport::Config(config.snapshot(env.block.time)),
+    };
+
+    Ok(Response::new()
+        .add_message(IbcMsg::SendPacket {
+            channel_id: gov_channel,
+            data: to_json_binary(&report)?,
+            timeout: IbcTimeout::from(env.block.time.plus_seconds(config.timeout)),
+        })
+        .add_attribute("action", "send_report")
+        .add_attribute("kind", kind.to_string()))
+}
+
+/// Transfers ASTRO to the maker on the main chain or to the receiver set by the owner
+fn transfer_astro(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+    memo: Option<String>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Partial transfers by anyone else would hold the rest of the balance until the cooldown ends
+    if (amount.is_some() || memo.is_some() || receiver.is_some()) && info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(last_transfer) = LAST_ASTRO_TRANSFER.may_load(deps.storage)? {
+        let next_transfer = last_transfer.plus_seconds(config.astro_transfer_cooldown);
+        if env.block.time < next_transfer {
+            return Err(ContractError::AstroTransferCooldown { next_transfer });
+        }
+    }
+
+    let astro_balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.astro_denom)?;
+
+    if astro_balance.amount.is_zero() {
+        return Err(ContractError::NoAstroBalance {});
+    }
+
+    // Send the whole astro balance unless the amount is specified
+    let amount = amount.unwrap_or(astro_balance.amount);
+    if amount.is_zero() || amount > astro_balance.amount {
+        return Err(ContractError::InvalidAstroAmount {
+            amount,
+            balance: astro_balance.amount,
+        });
+    }
+    if amount < config.min_astro_transfer {
+        return Err(ContractError::AstroTransferTooSmall {
+            amount,
+            min: config.min_astro_transfer,
+        });
+    }
+
+    LAST_ASTRO_TRANSFER.save(deps.storage, &env.block.time)?;
+
+    let receiver = receiver.unwrap_or(config.main_maker);
+    let amount = coin(amount.u128(), config.astro_denom);
+    let timeout = env.block.time.plus_seconds(config.timeout);
+    let msg = match memo {
+        Some(memo) => ibc_transfer_with_memo(
+            env.contract.address.to_string(),
+            config.transfer_channel,
+            receiver.clone(),
+            amount.clone(),
+            timeout,
+            memo,
+        ),
+        None => CosmosMsg::Ibc(IbcMsg::Transfer {
+            channel_id: config.transfer_channel,
+            to_address: receiver.clone(),
+            amount: amount.clone(),
+            timeout: IbcTimeout::from(timeout),
+        }),
+    };
+
+    Ok(Response::new().add_message(msg).add_attributes([
+        attr("action", "transfer_astro"),
+        attr("receiver", receiver),
+        attr("amount", amount.to_string()),
+    ]))
+}
+
+/// Applies the CheckMessages rules to the message.
+/// Well-known Stargate messages are decoded and the messages they wrap are checked recursively
+fn check_message<M>(
+    api: &dyn Api,
+    env: &Env,
+    msg: &CosmosMsg<M>,
+    depth: u32,
+) -> Result<(), ContractError> {
+    match msg {
+        CosmosMsg::Wasm(
+            WasmMsg::Migrate { contract_addr, .. } | WasmMsg::UpdateAdmin { contract_addr, .. },
+        ) if api.addr_validate(contract_addr)? == env.contract.address => {
+            Err(StdError::generic_err(
+                "Can't check messages with a migration or update admin message of the contract itself",
+            )
+            .into())
+        }
+        // Covers both authz MsgGrant and feegrant MsgGrantAllowance
+        CosmosMsg::Stargate { type_url, .. } if type_url.contains("MsgGrant") => Err(
+            StdError::generic_err("Can't check messages with a MsgGrant message").into(),
+        ),
+        CosmosMsg::Stargate { type_url, value } => {
+            if let Some(wrapped) = decode_stargate::<M>(type_url, value)? {
+                if depth >= MAX_NESTING_DEPTH {
+                    return Err(StdError::generic_err(format!(
+                        "Can't check messages nested deeper than {MAX_NESTING_DEPTH} levels"
+                    ))
+                    .into());
+                }
+                wrapped
+                    .iter()
+                    .try_for_each(|msg| check_message(api, env, msg, depth + 1))?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
     }
 }
 
 /// Checks that proposal messages are correct.
 pub fn check_messages<M>(
+    deps: Deps,
     info: MessageInfo,
-    api: &dyn Api,
     env: Env,
     mut messages: Vec<CosmosMsg<M>>,
 ) -> Result<Response<M>, ContractError>
@@ -134,19 +743,10 @@ where
         ContractError::Unauthorized {}
     );
 
-    messages.iter().try_for_each(|msg| match msg {
-        CosmosMsg::Wasm(
-            WasmMsg::Migrate { contract_addr, .. } | WasmMsg::UpdateAdmin { contract_addr, .. },
-        ) if api.addr_validate(contract_addr)? == env.contract.address => {
-            Err(StdError::generic_err(
-                "Can't check messages with a migration or update admin message of the contract itself",
-            ))
-        }
-        CosmosMsg::Stargate { type_url, .. } if type_url.contains("MsgGrant") => Err(
-            StdError::generic_err("Can't check messages with a MsgGrant message"),
-        ),
-        _ => Ok(()),
-    })?;
+    messages
+        .iter()
+        .try_for_each(|msg| check_message(deps.api, &env, msg, 0))?;
+    check_policy(&load_policy(deps.storage)?, &messages)?;
 
     messages.push(
         wasm_execute(
@@ -163,7 +763,7 @@ where
 }
 
 pub fn exec_from_multisig<M>(
-    querier: QuerierWrapper,
+    deps: Deps,
     info: MessageInfo,
     env: Env,
     messages: Vec<CosmosMsg<M>>,
@@ -171,7 +771,8 @@ pub fn exec_from_multisig<M>(
 where
     M: CustomMsg,
 {
-    match querier
+    match deps
+        .querier
         .query_wasm_contract_info(&env.contract.address)?
         .admin
     {
@@ -182,17 +783,67 @@ where
         }
         _ => Ok(()),
     }?;
+    check_policy(&load_policy(deps.storage)?, &messages)?;
 
     Ok(Response::new().add_messages(messages))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::ProposalState { id } => {
             let state = RESULTS.load(deps.storage, id)?;
             Ok(to_json_binary(&state)?)
         }
+        QueryMsg::Config {} => Ok(to_json_binary(&CONFIG.load(deps.storage)?.to_response())?),
+        QueryMsg::OwnershipProposal {} => {
+            let proposal = OWNERSHIP_PROPOSAL.may_load(deps.storage)?.map(|proposal| {
+                OwnershipProposalResponse {
+                    owner: proposal.owner,
+                    ttl: proposal.ttl,
+                }
+            });
+            Ok(to_json_binary(&proposal)?)
+        }
+        QueryMsg::LastHubSignal {} => {
+            let time = LATEST_HUB_SIGNAL_TIME.load(deps.storage)?;
+            let outage_deadline = time.plus_seconds(CONFIG.load(deps.storage)?.max_signal_outage);
+            Ok(to_json_binary(&LastHubSignalResponse {
+                time,
+                outage_deadline,
+                lost: outage_deadline < env.block.time,
+            })?)
+        }
+        QueryMsg::ChunkedUpload { id } => Ok(to_json_binary(&UPLOADS.may_load(deps.storage, id)?)?),
+        QueryMsg::MessagePolicy {} => Ok(to_json_binary(&load_policy(deps.storage)?)?),
+        QueryMsg::MessageResults { id } => {
+            let results = MESSAGE_RESULTS
+                .prefix(id)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, result)| result))
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&results)?)
+        }
+        QueryMsg::ControllerEpoch {} => Ok(to_json_binary(
+            &CONTROLLER_EPOCH.may_load(deps.storage)?.unwrap_or_default(),
+        )?),
+        QueryMsg::QueuedProposal { id } => {
+            Ok(to_json_binary(&QUEUED_PROPOSALS.load(deps.storage, id)?)?)
+        }
+        QueryMsg::QueuedProposals { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let proposals = QUEUED_PROPOSALS
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, proposal)| proposal))
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&proposals)?)
+        }
     }
 }
 
@@ -202,7 +853,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
 
     match contract_version.contract.as_ref() {
         "astro-satellite" => match contract_version.version.as_ref() {
-            "1.1.0-hubmove" | "1.2.0" => {}
+            "1.1.0-hubmove" | "1.2.0" | "1.2.1" => migrate_config(deps.storage)?,
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -220,3 +871,479 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
         ("new_contract_version", CONTRACT_VERSION),
     ]))
 }
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+    use cosmwasm_std::testing::{mock_ibc_packet_recv, mock_info};
+    use cosmwasm_std::{attr, coins, Addr, Empty, IbcMsg, ReplyOn, SubMsgResponse};
+    use prost::Message;
+
+    use astro_satellite_package::{
+        ConfigSnapshot, ExecutionMode, ProposalOutcomeReport, SatelliteMsg, UpdateConfigMsg,
+    };
+
+    use super::*;
+    use crate::ibc::ibc_packet_receive;
+    use crate::stargate::MsgTransfer;
+    use crate::state::{CHANNEL_VERSIONS, MAX_ASTRO_TRANSFER_COOLDOWN, MAX_TIMELOCK};
+    use crate::test_utils::{
+        connect_gov_channel, init_contract, mock_all, update_config, GOV_CHANNEL, OWNER,
+    };
+
+    #[test]
+    fn transfer_astro() {
+        let (mut deps, mut env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        let transfer_config = |astro_transfer_cooldown: u64| UpdateConfigMsg {
+            astro_denom: Some("uastro".to_string()),
+            main_maker: Some("maker".to_string()),
+            transfer_channel: Some("channel-1".to_string()),
+            min_astro_transfer: Some(100u128.into()),
+            astro_transfer_cooldown: Some(astro_transfer_cooldown),
+            ..Default::default()
+        };
+        let err = update_config(
+            deps.as_mut(),
+            transfer_config(MAX_ASTRO_TRANSFER_COOLDOWN + 1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AstroTransferCooldownLimitsError {});
+        update_config(deps.as_mut(), transfer_config(3600)).unwrap();
+
+        let transfer = |amount: Option<u128>, memo: Option<&str>, receiver: Option<&str>| {
+            ExecuteMsg::TransferAstro {
+                amount: amount.map(Into::into),
+                memo: memo.map(ToString::to_string),
+                receiver: receiver.map(ToString::to_string),
+            }
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            transfer(None, None, None),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoAstroBalance {});
+
+        deps.querier
+            .update_balance(env.contract.address.as_str(), coins(1000, "uastro"));
+
+        // Only the owner can transfer a part of the balance, attach a memo or override the receiver
+        for msg in [
+            transfer(Some(500), None, None),
+            transfer(None, Some("memo"), None),
+            transfer(None, None, Some("receiver")),
+        ] {
+            let err =
+                execute(deps.as_mut(), env.clone(), mock_info("random", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+        }
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            transfer(Some(2000), None, None),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidAstroAmount {
+                amount: 2000u128.into(),
+                balance: 1000u128.into(),
+            }
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            transfer(Some(50), None, None),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AstroTransferTooSmall {
+                amount: 50u128.into(),
+                min: 100u128.into(),
+            }
+        );
+
+        // The whole balance is sent to the maker by default
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            transfer(None, None, None),
+        )
+        .unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: "channel-1".to_string(),
+                to_address: "maker".to_string(),
+                amount: coin(1000, "uastro"),
+                timeout: env.block.time.plus_seconds(60).into(),
+            })
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            transfer(Some(500), None, None),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AstroTransferCooldown {
+                next_transfer: env.block.time.plus_seconds(3600),
+            }
+        );
+
+        // Transfers with a memo are sent as ICS-20 MsgTransfer
+        env.block.time = env.block.time.plus_seconds(3600);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            transfer(Some(500), Some("memo"), Some("receiver")),
+        )
+        .unwrap();
+        let CosmosMsg::Stargate { type_url, value } = &resp.messages[0].msg else {
+            panic!("Unexpected message: {:?}", resp.messages[0].msg)
+        };
+        assert_eq!(type_url, "/ibc.applications.transfer.v1.MsgTransfer");
+        assert_eq!(
+            MsgTransfer::decode(value.as_slice()).unwrap(),
+            MsgTransfer {
+                source_port: "transfer".to_string(),
+                source_channel: "channel-1".to_string(),
+                token: Some(ProtoCoin {
+                    denom: "uastro".to_string(),
+                    amount: "500".to_string(),
+                }),
+                sender: env.contract.address.to_string(),
+                receiver: "receiver".to_string(),
+                timeout_timestamp: env.block.time.plus_seconds(60).nanos(),
+                memo: "memo".to_string(),
+            }
+        );
+        assert!(resp.attributes.contains(&attr("receiver", "receiver")));
+    }
+
+    #[test]
+    fn send_report() {
+        let (mut deps, mut env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        let report_status = ExecuteMsg::SendReport {
+            kind: ReportKind::Status,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            report_status.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::GovernanceChannelNotFound {});
+
+        connect_gov_channel(deps.as_mut());
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            report_status.clone(),
+        )
+        .unwrap();
+        match &resp.messages[0].msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket {
+                channel_id, data, ..
+            }) => {
+                assert_eq!(channel_id, GOV_CHANNEL);
+                assert_eq!(
+                    from_json::<SatelliteReport>(data).unwrap(),
+                    SatelliteReport::Status(StatusReport {
+                        time: env.block.time,
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        latest_hub_signal_time: env.block.time,
+                        last_executed_proposal: None,
+                    })
+                );
+            }
+            _ => panic!("Unexpected message"),
+        }
+
+        // Reports of the same kind are rate limited unless sent by the owner
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            report_status.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ReportTooEarly {
+                kind: "status".to_string(),
+                next_report: env.block.time.plus_seconds(REPORT_INTERVAL),
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::SendReport {
+                kind: ReportKind::Balances,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            report_status.clone(),
+        )
+        .unwrap();
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::SendReport {
+                kind: ReportKind::Config,
+            },
+        )
+        .unwrap();
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        match &resp.messages[0].msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket { data, .. }) => assert_eq!(
+                from_json::<SatelliteReport>(data).unwrap(),
+                SatelliteReport::Config(ConfigSnapshot {
+                    time: env.block.time,
+                    owner: config.owner,
+                    emergency_owner: config.emergency_owner,
+                    max_signal_outage: config.max_signal_outage,
+                    astro_denom: config.astro_denom,
+                    main_controller_port: config.main_controller_port,
+                    main_maker: config.main_maker,
+                    gov_channel: Some(GOV_CHANNEL.to_string()),
+                    transfer_channel: config.transfer_channel,
+                    timeout: config.timeout,
+                })
+            ),
+            _ => panic!("Unexpected message"),
+        }
+
+        env.block.time = env.block.time.plus_seconds(REPORT_INTERVAL);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            report_status.clone(),
+        )
+        .unwrap();
+
+        // Reports are not supported by the v1 protocol
+        CHANNEL_VERSIONS
+            .save(deps.as_mut().storage, GOV_CHANNEL, &IbcVersion::V1)
+            .unwrap();
+        let err = execute(deps.as_mut(), env, mock_info("keeper", &[]), report_status).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnsupportedPacket {
+                version: IbcVersion::V1
+            }
+        );
+    }
+
+    #[test]
+    fn queued_proposals() {
+        let (mut deps, mut env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info);
+        connect_gov_channel(deps.as_mut());
+
+        let err = update_config(
+            deps.as_mut(),
+            UpdateConfigMsg {
+                timelock: Some(MAX_TIMELOCK + 1),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TimelockLimitsError {});
+        update_config(
+            deps.as_mut(),
+            UpdateConfigMsg {
+                timelock: Some(3600),
+                guardian: Some("guardian".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for id in [1, 2] {
+            let proposal = SatelliteMsg::ExecuteProposal {
+                id,
+                messages: vec![CosmosMsg::Custom(Empty {})],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            };
+            let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal).unwrap();
+            ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        }
+        let queued = QUEUED_PROPOSALS.load(deps.as_ref().storage, 1).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::ExecuteQueued { id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TimelockNotExpired {
+                id: 1,
+                eta: queued.eta
+            }
+        );
+
+        // Only the guardian or the emergency owner can veto proposals
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::VetoQueued { id: 2 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::VetoQueued { id: 2 },
+        )
+        .unwrap();
+        match &resp.messages[0].msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket { data, .. }) => assert_eq!(
+                from_json::<SatelliteReport>(data).unwrap(),
+                SatelliteReport::ProposalOutcome(ProposalOutcomeReport {
+                    time: env.block.time,
+                    id: 2,
+                    status: QueueStatus::Vetoed,
+                })
+            ),
+            _ => panic!("Unexpected message"),
+        }
+
+        // Anyone can execute the proposal once the timelock has expired
+        env.block.time = queued.eta;
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::VetoQueued { id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::VetoPeriodExpired {
+                id: 1,
+                eta: queued.eta
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::ExecuteQueued { id: 2 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ProposalNotQueued { id: 2 });
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::ExecuteQueued { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(resp.messages[0].id, EXECUTE_QUEUED_ID);
+        assert_eq!(resp.messages[0].reply_on, ReplyOn::Always);
+
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: EXECUTE_QUEUED_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        match &resp.messages[0].msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket { data, .. }) => assert_eq!(
+                from_json::<SatelliteReport>(data).unwrap(),
+                SatelliteReport::ProposalOutcome(ProposalOutcomeReport {
+                    time: env.block.time,
+                    id: 1,
+                    status: QueueStatus::Executed,
+                })
+            ),
+            _ => panic!("Unexpected message"),
+        }
+        assert!(RESULTS.has(deps.as_ref().storage, 1));
+    }
+
+    #[test]
+    fn test_migrate() {
+        let (mut deps, env, _) = mock_all(OWNER);
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.2.1").unwrap();
+        deps.storage.set(
+            b"config",
+            br#"{"owner":"owner","max_signal_outage":1209600,"emergency_owner":"emergency","astro_denom":"uastro","main_controller_port":"wasm.controller","main_maker":"maker","gov_channel":"channel-20","transfer_channel":"channel-1","timeout":60}"#,
+        );
+
+        migrate(deps.as_mut(), env.clone(), Empty {}).unwrap();
+
+        // The parameters introduced after v1.2.x are disabled
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                owner: Addr::unchecked("owner"),
+                max_signal_outage: 1209600,
+                emergency_owner: Addr::unchecked("emergency"),
+                astro_denom: "uastro".to_string(),
+                main_controller_port: "wasm.controller".to_string(),
+                main_maker: "maker".to_string(),
+                gov_channel: Some("channel-20".to_string()),
+                transfer_channel: "channel-1".to_string(),
+                timeout: 60,
+                timelock: 0,
+                guardian: None,
+                max_proposal_age: 0,
+                min_astro_transfer: Uint128::zero(),
+                astro_transfer_cooldown: 0,
+            }
+        );
+        assert_eq!(
+            get_contract_version(deps.as_ref().storage).unwrap().version,
+            CONTRACT_VERSION
+        );
+
+        let err = migrate(deps.as_mut(), env, Empty {}).unwrap_err();
+        assert_eq!(err, ContractError::MigrationError {});
+    }
+}
@@ -1,7 +1,10 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
 use thiserror::Error;
 
-use astroport_ibc::{SIGNAL_OUTAGE_LIMITS, TIMEOUT_LIMITS};
+use astro_satellite_package::{AckError, AckErrorCode};
+use astroport_ibc::{IbcVersion, SIGNAL_OUTAGE_LIMITS, TIMEOUT_LIMITS};
+
+use crate::state::{MAX_ASTRO_TRANSFER_COOLDOWN, MAX_TIMELOCK, PROPOSAL_AGE_LIMITS};
 
 /// Never is a placeholder to ensure we don't return any errors
 #[derive(Error, Debug)]
@@ -54,4 +57,116 @@ pub enum ContractError {
 
     #[error("Satellite doesn't have any ASTRO in balance")]
     NoAstroBalance {},
+
+    #[error("Can't transfer {amount} ASTRO with {balance} ASTRO in balance")]
+    InvalidAstroAmount { amount: Uint128, balance: Uint128 },
+
+    #[error("ASTRO transfer of {amount} is below the minimum of {min}")]
+    AstroTransferTooSmall { amount: Uint128, min: Uint128 },
+
+    #[error("ASTRO can't be transferred before {next_transfer}")]
+    AstroTransferCooldown { next_transfer: Timestamp },
+
+    #[error("Next {kind} report can't be sent before {next_report}")]
+    ReportTooEarly {
+        kind: String,
+        next_report: Timestamp,
+    },
+
+    #[error("Version `{version}` is not supported")]
+    UnknownVersion { version: String },
+
+    #[error("Packet is not supported by {version}")]
+    UnsupportedPacket { version: IbcVersion },
+
+    #[error("Proposals can't be queued by the timelock over {version} channels")]
+    TimelockNotSupported { version: IbcVersion },
+
+    #[error("Timelock must not exceed {0} seconds", MAX_TIMELOCK)]
+    TimelockLimitsError {},
+
+    #[error(
+        "ASTRO transfer cooldown must not exceed {0} seconds",
+        MAX_ASTRO_TRANSFER_COOLDOWN
+    )]
+    AstroTransferCooldownLimitsError {},
+
+    #[error(
+        "Max proposal age must be 0 or within {0}-{1} seconds",
+        PROPOSAL_AGE_LIMITS.start(),
+        PROPOSAL_AGE_LIMITS.end()
+    )]
+    ProposalAgeLimitsError {},
+
+    #[error("Proposal {id} is already queued")]
+    ProposalAlreadyQueued { id: u64 },
+
+    #[error("Proposal {id} is not queued")]
+    ProposalNotQueued { id: u64 },
+
+    #[error("Proposal {id} can't be executed before {eta}")]
+    TimelockNotExpired { id: u64, eta: Timestamp },
+
+    #[error("Proposal {id} can't be vetoed after its timelock expired at {eta}")]
+    VetoPeriodExpired { id: u64, eta: Timestamp },
+
+    #[error("Chunk {index} of proposal {id} is out of range. The proposal has {total} chunks")]
+    InvalidChunk { id: u64, index: u32, total: u32 },
+
+    #[error("Content hash of proposal {id} doesn't match the received chunks")]
+    ChunkHashMismatch { id: u64 },
+
+    #[error("Upload of proposal {id} can't be cleared before {expires_at}")]
+    UploadNotExpired { id: u64, expires_at: Timestamp },
+
+    #[error("Message {index} is rejected by the message policy: {reason}")]
+    MessageDenied { index: u32, reason: String },
+
+    #[error("Proposal {id} is already executed")]
+    ProposalAlreadyExecuted { id: u64 },
+
+    #[error("Proposal {id} was sent in epoch {epoch} which is superseded by epoch {current}")]
+    SupersededEpoch { id: u64, epoch: u64, current: u64 },
+
+    #[error("Proposal {id} sent at {sent_at} is older than {max_age} seconds")]
+    ProposalExpired {
+        id: u64,
+        sent_at: Timestamp,
+        max_age: u64,
+    },
+}
+
+impl ContractError {
+    /// Returns the stable code the error is acknowledged with
+    pub fn ack_code(&self) -> AckErrorCode {
+        match self {
+            ContractError::GovernanceChannelNotFound {} => AckErrorCode::GovernanceNotEstablished,
+            ContractError::InvalidGovernanceChannel { .. } => AckErrorCode::InvalidChannel,
+            ContractError::Std(StdError::ParseErr { .. }) => AckErrorCode::InvalidPacket,
+            ContractError::UnknownVersion { .. } => AckErrorCode::UnknownVersion,
+            ContractError::UnsupportedPacket { .. }
+            | ContractError::TimelockNotSupported { .. } => AckErrorCode::UnsupportedPacket,
+            ContractError::ProposalExpired { .. } => AckErrorCode::StaleProposal,
+            ContractError::ProposalAlreadyQueued { .. } => AckErrorCode::AlreadyQueued,
+            ContractError::ProposalAlreadyExecuted { .. } => AckErrorCode::AlreadyExecuted,
+            ContractError::SupersededEpoch { .. } => AckErrorCode::SupersededEpoch,
+            ContractError::MessageDenied { .. } => AckErrorCode::PolicyViolation,
+            ContractError::InvalidChunk { .. } | ContractError::ChunkHashMismatch { .. } => {
+                AckErrorCode::InvalidChunk
+            }
+            _ => AckErrorCode::Unknown,
+        }
+    }
+
+    /// Returns the structured error the packet is acknowledged with
+    pub fn to_ack_error(&self) -> AckError {
+        AckError {
+            code: self.ack_code(),
+            message_index: match self {
+                ContractError::MessageDenied { index, .. } => Some(*index),
+                _ => None,
+            },
+            error: self.to_string(),
+        }
+    }
 }
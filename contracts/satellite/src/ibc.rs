@@ -1,20 +1,29 @@
 use std::fmt::Display;
 
 use cosmwasm_std::{
-    entry_point, from_json, to_json_binary, Binary, DepsMut, Env, Ibc3ChannelOpenResponse,
-    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
-    IbcChannelOpenResponse, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
-    IbcReceiveResponse, ReplyOn, StdError, StdResult, SubMsg,
+    entry_point, from_json, to_json_binary, wasm_execute, Binary, CosmosMsg, DepsMut, Empty, Env,
+    Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcChannelOpenResponse, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcReceiveResponse, ReplyOn, StdError, StdResult, Storage, SubMsg,
+    Timestamp,
 };
 
-use astro_satellite_package::{IbcAckResult, SatelliteMsg};
+use astro_satellite_package::{
+    content_hash, AckError, BatchProposal, BatchProposalResult, ChunkedUpload, ExecuteMsg,
+    ExecutionMode, ExecutionReceipt, IbcAckResult, IbcVersion, QueueStatus, QueuedProposal,
+    SatelliteMsg, CHUNK_ACK, QUEUED_ACK,
+};
 use ibc_controller_package::IbcProposal;
 
-use crate::contract::RECEIVE_ID;
+use crate::contract::{EXECUTE_BATCHED_ID, EXECUTE_PROPOSAL_ID, RECEIVE_ID, SIMULATION_ID};
 use crate::error::{ContractError, Never};
-use crate::state::{store_proposal, CONFIG, LATEST_HUB_SIGNAL_TIME, REPLY_DATA};
+use crate::policy::{check_policy, load_policy};
+use crate::state::{
+    channel_version, clear_upload, store_proposal, Config, BATCH_PENDING, BATCH_RESULTS,
+    CHANNEL_VERSIONS, CONFIG, CONTROLLER_EPOCH, EXECUTION_PROGRESS, LATEST_HUB_SIGNAL_TIME,
+    PENDING_PROPOSAL, QUEUED_PROPOSALS, REPLY_DATA, RESULTS, UPLOADS, UPLOAD_CHUNKS, UPLOAD_TTL,
+};
 
-pub const IBC_APP_VERSION: &str = "astroport-ibc-v1";
 pub const IBC_ORDERING: IbcOrder = IbcOrder::Unordered;
 
 /// Create a serialized success message
@@ -22,11 +31,45 @@ pub fn ack_ok() -> Binary {
     to_json_binary(&IbcAckResult::Ok(b"ok".into())).unwrap()
 }
 
+/// Create a serialized message of the proposal queued by the timelock
+pub fn ack_queued() -> Binary {
+    to_json_binary(&IbcAckResult::Ok(QUEUED_ACK.into())).unwrap()
+}
+
+/// Create a serialized success message carrying the proposal execution receipt
+pub fn ack_receipt(receipt: &ExecutionReceipt) -> StdResult<Binary> {
+    to_json_binary(&IbcAckResult::Ok(to_json_binary(receipt)?))
+}
+
+/// Structured error result of the batched proposal
+fn ack_result_error(err: &ContractError) -> StdResult<IbcAckResult> {
+    Ok(IbcAckResult::Error(err.to_ack_error().encode()?))
+}
+
+/// Create a serialized message with the results of the batched proposals
+pub fn ack_batch(results: &[BatchProposalResult]) -> StdResult<Binary> {
+    to_json_binary(&IbcAckResult::Ok(to_json_binary(results)?))
+}
+
+/// Create a serialized message of the proposal chunk which didn't complete the upload
+pub fn ack_chunk() -> Binary {
+    to_json_binary(&IbcAckResult::Ok(CHUNK_ACK.into())).unwrap()
+}
+
 /// Create a serialized error message
 pub fn ack_fail(err: impl Display) -> Binary {
     to_json_binary(&IbcAckResult::Error(err.to_string())).unwrap()
 }
 
+/// Create a serialized structured error message.
+/// v1 controllers receive the raw error text only
+pub fn ack_error(version: IbcVersion, error: &AckError) -> StdResult<Binary> {
+    match version {
+        IbcVersion::V1 => Ok(ack_fail(&error.error)),
+        _ => to_json_binary(&IbcAckResult::Error(error.encode()?)),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_channel_open(
     _deps: DepsMut,
@@ -40,22 +83,22 @@ pub fn ibc_channel_open(
             "Ordering is invalid. The channel must be unordered",
         ));
     }
-    if channel.version != IBC_APP_VERSION {
-        return Err(StdError::generic_err(format!(
-            "Must set version to `{IBC_APP_VERSION}`"
-        )));
-    }
-
-    if let Some(counter_version) = msg.counterparty_version() {
-        if counter_version != IBC_APP_VERSION {
-            return Err(StdError::generic_err(format!(
-                "Counterparty version must be `{IBC_APP_VERSION}`"
-            )));
-        }
-    }
+    let version = match msg.counterparty_version() {
+        // Agree on the highest version supported by both sides
+        Some(counter_version) => IbcVersion::negotiate(counter_version).ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Counterparty version `{counter_version}` is not supported"
+            ))
+        })?,
+        // Propose the latest version unless the relayer requested a specific one
+        None if channel.version.is_empty() => IbcVersion::LATEST,
+        None => IbcVersion::parse(&channel.version).ok_or_else(|| {
+            StdError::generic_err(format!("Version `{}` is not supported", channel.version))
+        })?,
+    };
 
     Ok(Some(Ibc3ChannelOpenResponse {
-        version: IBC_APP_VERSION.to_string(),
+        version: version.to_string(),
     }))
 }
 
@@ -67,13 +110,11 @@ pub fn ibc_channel_connect(
 ) -> Result<IbcBasicResponse, ContractError> {
     let channel = msg.channel();
 
-    if let Some(counter_version) = msg.counterparty_version() {
-        if counter_version != IBC_APP_VERSION {
-            return Err(ContractError::Std(StdError::generic_err(format!(
-                "Counterparty version must be `{IBC_APP_VERSION}`"
-            ))));
-        }
-    }
+    // The version chosen by the counterparty on OpenTry is final
+    let version = msg.counterparty_version().unwrap_or(&channel.version);
+    let version = IbcVersion::parse(version).ok_or_else(|| ContractError::UnknownVersion {
+        version: version.to_string(),
+    })?;
 
     let config = CONFIG.load(deps.storage)?;
     match config.gov_channel {
@@ -90,9 +131,12 @@ pub fn ibc_channel_connect(
         }
     }
 
+    CHANNEL_VERSIONS.save(deps.storage, &channel.endpoint.channel_id, &version)?;
+
     Ok(IbcBasicResponse::new()
         .add_attribute("action", "ibc_connect")
-        .add_attribute("channel_id", &channel.endpoint.channel_id))
+        .add_attribute("channel_id", &channel.endpoint.channel_id)
+        .add_attribute("version", version.as_str()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -102,10 +146,14 @@ pub fn ibc_packet_receive(
     env: Env,
     msg: IbcPacketReceiveMsg,
 ) -> Result<IbcReceiveResponse, Never> {
+    let version =
+        channel_version(deps.storage, &msg.packet.dest.channel_id).unwrap_or(IbcVersion::V1);
+
     do_packet_receive(deps, env, msg).or_else(|err| {
+        let ack = ack_error(version, &err.to_ack_error()).unwrap_or_else(|_| ack_fail(&err));
         Ok(IbcReceiveResponse::new()
             .add_attribute("action", "ibc_packet_receive")
-            .set_ack(ack_fail(err)))
+            .set_ack(ack))
     })
 }
 
@@ -126,11 +174,18 @@ fn do_packet_receive(
         _ => {}
     }
 
+    let version = channel_version(deps.storage, &msg.packet.dest.channel_id)?;
     // TODO: Remove or_else and map_err method calls once the controller is upgraded to v1.0.0
-    let satellite_msg = from_json(&msg.packet.data)
+    let satellite_msg = SatelliteMsg::decode(&msg.packet.data, version)
         .or_else(|_| {
             let IbcProposal { id, messages } = from_json(&msg.packet.data)?;
-            Ok(SatelliteMsg::ExecuteProposal { id, messages })
+            Ok(SatelliteMsg::ExecuteProposal {
+                id,
+                messages,
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            })
         })
         .map_err(ContractError::Std)?;
 
@@ -139,19 +194,131 @@ fn do_packet_receive(
         .set_ack(ack_ok());
 
     match satellite_msg {
-        SatelliteMsg::ExecuteProposal { id, messages } => {
-            if !messages.is_empty() {
-                let mut messages: Vec<_> = messages.into_iter().map(SubMsg::new).collect();
-                if let Some(last_msg) = messages.last_mut() {
-                    last_msg.reply_on = ReplyOn::Success;
-                    last_msg.id = RECEIVE_ID;
+        SatelliteMsg::ExecuteProposal {
+            id,
+            messages,
+            sent_at,
+            epoch,
+            mode,
+        } => {
+            // Controllers which don't send epochs are in the initial one
+            check_replay(deps.storage, id, epoch.unwrap_or_default())?;
+            check_proposal_age(&config, &env, id, sent_at)?;
+            check_policy(&load_policy(deps.storage)?, &messages)?;
+            response = run_proposal(
+                deps.branch(),
+                &env,
+                &config,
+                version,
+                BatchProposal { id, messages, mode },
+                response,
+            )?;
+        }
+        SatelliteMsg::ExecuteChunk {
+            id,
+            index,
+            total,
+            hash,
+            data,
+            sent_at,
+            epoch,
+            mode,
+            ..
+        } => {
+            check_replay(deps.storage, id, epoch)?;
+            check_proposal_age(&config, &env, id, Some(sent_at))?;
+            response = response
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("chunk", index.to_string());
+
+            // The proposal is executed once the last missing chunk has arrived
+            match receive_chunk(deps.storage, &env, id, index, total, hash, data)? {
+                Some(messages) => {
+                    check_policy(&load_policy(deps.storage)?, &messages)?;
+                    response = run_proposal(
+                        deps.branch(),
+                        &env,
+                        &config,
+                        version,
+                        BatchProposal { id, messages, mode },
+                        response,
+                    )?
                 }
-                REPLY_DATA.save(deps.storage, &id)?;
-                response = response.add_submessages(messages)
-            } else {
-                store_proposal(deps.branch(), env.clone(), id)?;
+                None => response = response.set_ack(ack_chunk()),
             }
         }
+        SatelliteMsg::SimulateProposal { id, messages } => {
+            // Messages the policy rejects would never be executed
+            check_policy(&load_policy(deps.storage)?, &messages)?;
+            // The simulation reply overrides the acknowledgement with the result
+            response = response
+                .add_attribute("simulation_id", id.to_string())
+                .add_submessage(SubMsg::reply_always(
+                    wasm_execute(
+                        &env.contract.address,
+                        &ExecuteMsg::<Empty>::RunSimulation { id, messages },
+                        vec![],
+                    )?,
+                    SIMULATION_ID,
+                ));
+        }
+        SatelliteMsg::ExecuteBatch {
+            proposals,
+            sent_at,
+            epoch,
+        } => {
+            let policy = load_policy(deps.storage)?;
+            let mut results = vec![];
+            let mut pending = vec![];
+            for proposal in proposals {
+                let id = proposal.id;
+                let checked = check_replay(deps.storage, id, epoch)
+                    .and_then(|_| check_proposal_age(&config, &env, id, Some(sent_at)))
+                    .and_then(|_| check_policy(&policy, &proposal.messages));
+                let result = match checked {
+                    Err(err) => ack_result_error(&err)?,
+                    Ok(_) if config.timelock > 0 => {
+                        match queue_proposal(deps.storage, &env, &config, proposal) {
+                            Ok(_) => IbcAckResult::Ok(QUEUED_ACK.into()),
+                            Err(err) => ack_result_error(&err)?,
+                        }
+                    }
+                    Ok(_) if proposal.messages.is_empty() => {
+                        store_proposal(deps.branch(), env.clone(), id)?;
+                        IbcAckResult::Ok(to_json_binary(&ExecutionReceipt::new(
+                            &env.block,
+                            vec![],
+                        ))?)
+                    }
+                    Ok(_) => {
+                        // Each proposal is executed in its own submessage, so that its failure
+                        // doesn't revert the rest of the batch
+                        response = response.add_submessage(SubMsg::reply_always(
+                            wasm_execute(
+                                &env.contract.address,
+                                &ExecuteMsg::<Empty>::RunBatched {
+                                    id,
+                                    messages: proposal.messages,
+                                    mode: proposal.mode,
+                                },
+                                vec![],
+                            )?,
+                            EXECUTE_BATCHED_ID,
+                        ));
+                        pending.push(id);
+                        continue;
+                    }
+                };
+                results.push(BatchProposalResult { id, result });
+            }
+
+            // Replies of the batched proposals complete the acknowledgement
+            if !pending.is_empty() {
+                BATCH_PENDING.save(deps.storage, &pending)?;
+                BATCH_RESULTS.save(deps.storage, &results)?;
+            }
+            response = response.set_ack(ack_batch(&results)?);
+        }
         SatelliteMsg::Heartbeat {} => {}
     }
     LATEST_HUB_SIGNAL_TIME.save(deps.storage, &env.block.time)?;
@@ -159,22 +326,224 @@ fn do_packet_receive(
     Ok(response)
 }
 
+/// Executes the proposal right away or puts it in the timelock queue
+fn run_proposal(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    version: IbcVersion,
+    proposal: BatchProposal,
+    response: IbcReceiveResponse,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let id = proposal.id;
+    if config.timelock > 0 {
+        // v1 controllers would treat the plain success acknowledgement as executed proposal
+        if version == IbcVersion::V1 {
+            return Err(ContractError::TimelockNotSupported { version });
+        }
+        queue_proposal(deps.storage, env, config, proposal)?;
+        Ok(response
+            .add_attribute("proposal_id", id.to_string())
+            .set_ack(ack_queued()))
+    } else if proposal.messages.is_empty() {
+        store_proposal(deps, env.clone(), id)?;
+        match version {
+            IbcVersion::V1 => Ok(response),
+            _ => Ok(response.set_ack(ack_receipt(&ExecutionReceipt::new(&env.block, vec![]))?)),
+        }
+    } else if version == IbcVersion::V1 {
+        let mut messages: Vec<_> = proposal.messages.into_iter().map(SubMsg::new).collect();
+        if let Some(last_msg) = messages.last_mut() {
+            last_msg.reply_on = ReplyOn::Success;
+            last_msg.id = RECEIVE_ID;
+        }
+        REPLY_DATA.save(deps.storage, &id)?;
+        EXECUTION_PROGRESS.remove(deps.storage);
+        Ok(response.add_submessages(messages))
+    } else {
+        // The proposal is executed in a submessage, so that its failure is acknowledged
+        // with the index of the failing message rather than reverting the packet
+        PENDING_PROPOSAL.save(deps.storage, &proposal)?;
+        Ok(response.add_submessage(SubMsg::reply_always(
+            wasm_execute(
+                &env.contract.address,
+                &ExecuteMsg::<Empty>::RunBatched {
+                    id,
+                    messages: proposal.messages,
+                    mode: proposal.mode,
+                },
+                vec![],
+            )?,
+            EXECUTE_PROPOSAL_ID,
+        )))
+    }
+}
+
+/// Stores the proposal chunk.
+/// Returns the proposal messages once every chunk has arrived and the content hash matches
+fn receive_chunk(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+    index: u32,
+    total: u32,
+    hash: String,
+    data: Binary,
+) -> Result<Option<Vec<CosmosMsg>>, ContractError> {
+    if index >= total {
+        return Err(ContractError::InvalidChunk { id, index, total });
+    }
+
+    let mut upload = match UPLOADS.may_load(storage, id)? {
+        Some(upload)
+            if upload.hash == hash
+                && upload.total == total
+                && upload.expires_at >= env.block.time =>
+        {
+            upload
+        }
+        // Chunks of an abandoned upload or of another version of the proposal are discarded
+        stale => {
+            if stale.is_some() {
+                clear_upload(storage, id)?;
+            }
+            ChunkedUpload {
+                id,
+                hash,
+                total,
+                received: 0,
+                started_at: env.block.time,
+                expires_at: env.block.time.plus_seconds(UPLOAD_TTL),
+            }
+        }
+    };
+
+    // The controller may send the same chunk again after a retry
+    if !UPLOAD_CHUNKS.has(storage, (id, index)) {
+        UPLOAD_CHUNKS.save(storage, (id, index), &data)?;
+        upload.received += 1;
+    }
+
+    if upload.received < upload.total {
+        UPLOADS.save(storage, id, &upload)?;
+        return Ok(None);
+    }
+
+    let mut content = vec![];
+    for index in 0..upload.total {
+        content.extend(UPLOAD_CHUNKS.load(storage, (id, index))?.0);
+    }
+    clear_upload(storage, id)?;
+
+    if content_hash(&content) != upload.hash {
+        return Err(ContractError::ChunkHashMismatch { id });
+    }
+
+    Ok(Some(from_json(&content)?))
+}
+
+/// Rejects the proposal if it was sent by the hub too long ago.
+/// The packet may be relayed long after the hub sent it, even within the IBC timeout
+fn check_proposal_age(
+    config: &Config,
+    env: &Env,
+    id: u64,
+    sent_at: Option<Timestamp>,
+) -> Result<(), ContractError> {
+    // Proposals sent after the satellite block time are not stale
+    let expired = |sent_at: Timestamp| {
+        env.block
+            .time
+            .seconds()
+            .checked_sub(sent_at.seconds())
+            .is_some_and(|age| age > config.max_proposal_age)
+    };
+    match sent_at {
+        Some(sent_at) if config.max_proposal_age > 0 && expired(sent_at) => {
+            Err(ContractError::ProposalExpired {
+                id,
+                sent_at,
+                max_age: config.max_proposal_age,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Rejects proposals which were already executed or sent in a superseded controller epoch.
+/// A newer epoch supersedes the stored one
+fn check_replay(storage: &mut dyn Storage, id: u64, epoch: u64) -> Result<(), ContractError> {
+    let current = CONTROLLER_EPOCH.may_load(storage)?.unwrap_or_default();
+    if epoch < current {
+        return Err(ContractError::SupersededEpoch { id, epoch, current });
+    }
+    if epoch > current {
+        CONTROLLER_EPOCH.save(storage, &epoch)?;
+    }
+
+    if RESULTS.has(storage, id) {
+        return Err(ContractError::ProposalAlreadyExecuted { id });
+    }
+
+    Ok(())
+}
+
+/// Puts the proposal in the timelock queue
+fn queue_proposal(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    proposal: BatchProposal,
+) -> Result<(), ContractError> {
+    let BatchProposal { id, messages, mode } = proposal;
+    match QUEUED_PROPOSALS.may_load(storage, id)? {
+        // The controller retried the packet which was already queued
+        Some(proposal) if proposal.status == QueueStatus::Queued => Ok(()),
+        Some(proposal) if !matches!(proposal.status, QueueStatus::Failed { .. }) => {
+            Err(ContractError::ProposalAlreadyQueued { id })
+        }
+        _ => Ok(QUEUED_PROPOSALS.save(
+            storage,
+            id,
+            &QueuedProposal {
+                id,
+                messages,
+                queued_at: env.block.time,
+                eta: env.block.time.plus_seconds(config.timelock),
+                status: QueueStatus::Queued,
+                mode,
+            },
+        )?),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
+/// The satellite only sends reports, which can be sent again, thus timeouts are just logged
 pub fn ibc_packet_timeout(
     _deps: DepsMut,
     _env: Env,
-    _msg: IbcPacketTimeoutMsg,
+    msg: IbcPacketTimeoutMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
-    unimplemented!()
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "report_timeout")
+        .add_attribute("channel_id", msg.packet.src.channel_id))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_ack(
     _deps: DepsMut,
     _env: Env,
-    _msg: IbcPacketAckMsg,
+    msg: IbcPacketAckMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
-    unimplemented!()
+    let mut res = IbcBasicResponse::new()
+        .add_attribute("action", "report_ack")
+        .add_attribute("channel_id", msg.original_packet.src.channel_id);
+
+    if let IbcAckResult::Error(err) = from_json(&msg.acknowledgement.data)? {
+        res = res.add_attribute("error", err);
+    }
+
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -189,77 +558,34 @@ pub fn ibc_channel_close(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::contract::{execute, instantiate};
-    use astro_satellite_package::{ExecuteMsg, InstantiateMsg, UpdateConfigMsg};
+    use crate::contract::{
+        execute, query, reply, MAX_PROBED_MESSAGES, PROBE_ID, SIMULATION_STEP_ID,
+    };
+    use crate::state::{FAILURE_PROBE, PROPOSAL_AGE_LIMITS, RESULTS};
+    use crate::test_utils::{
+        connect_gov_channel, init_contract, mock_all, mock_ibc_channel_connect_ack, update_config,
+        CONTROLLER, GOV_CHANNEL, OWNER,
+    };
+    use astro_satellite_package::{
+        AckErrorCode, ExecuteMsg, MessageKind, MessagePolicy, MessageResult, PolicyList, QueryMsg,
+        SimulationResult, UpdateConfigMsg,
+    };
+    use cosmos_sdk_proto::cosmwasm::wasm::v1::MsgExecuteContractResponse;
     use cosmwasm_std::testing::{
-        mock_dependencies, mock_env, mock_ibc_channel, mock_ibc_packet_recv, mock_info, MockApi,
-        MockQuerier, MockStorage,
+        mock_ibc_packet_recv, mock_info, MockApi, MockQuerier, MockStorage,
     };
-    use cosmwasm_std::{CosmosMsg, Empty, MessageInfo, OwnedDeps};
-
-    pub const OWNER: &str = "owner";
-    pub const CONTROLLER: &str = "controller";
-    pub const GOV_CHANNEL: &str = "channel-20";
-
-    pub fn mock_all(
-        sender: &str,
-    ) -> (
-        OwnedDeps<MockStorage, MockApi, MockQuerier>,
-        Env,
-        MessageInfo,
-    ) {
-        let deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(sender, &[]);
-        (deps, env, info)
-    }
-
-    pub fn init_contract(mut deps: DepsMut, env: Env, info: MessageInfo) {
-        let err = instantiate(
-            deps.branch(),
-            env.clone(),
-            info.clone(),
-            InstantiateMsg {
-                owner: OWNER.to_string(),
-                astro_denom: "".to_string(),
-                transfer_channel: "".to_string(),
-                main_controller: CONTROLLER.to_string(),
-                main_maker: "".to_string(),
-                timeout: 0,
-                max_signal_outage: 1209600,
-                emergency_owner: OWNER.to_string(),
-            },
-        )
-        .unwrap_err();
-        assert_eq!(ContractError::TimeoutLimitsError {}, err);
-
-        instantiate(
-            deps,
-            env,
-            info,
-            InstantiateMsg {
-                owner: OWNER.to_string(),
-                astro_denom: "".to_string(),
-                transfer_channel: "".to_string(),
-                main_controller: CONTROLLER.to_string(),
-                main_maker: "".to_string(),
-                timeout: 60,
-                max_signal_outage: 1209600,
-                emergency_owner: OWNER.to_string(),
-            },
-        )
-        .unwrap();
-    }
+    use cosmwasm_std::{
+        attr, coins, to_json_vec, BankMsg, CosmosMsg, Empty, Event, Order, OwnedDeps, Reply,
+        Response, SubMsgResponse, SubMsgResult, WasmMsg,
+    };
+    use prost::Message;
 
-    fn mock_ibc_channel_connect_ack(
-        my_channel_id: &str,
-        order: IbcOrder,
-        version: &str,
-        their_port: &str,
-    ) -> IbcChannelConnectMsg {
-        let mut mocked_channel = mock_ibc_channel(my_channel_id, order, version);
-        mocked_channel.counterparty_endpoint.port_id = their_port.to_string();
-        IbcChannelConnectMsg::new_ack(mocked_channel, version)
+    /// Wraps the contract execution data the same way the chain does before replying
+    pub fn execute_response_data(data: Option<Binary>) -> Option<Binary> {
+        let response = MsgExecuteContractResponse {
+            data: data.map(|data| data.to_vec()).unwrap_or_default(),
+        };
+        Some(response.encode_to_vec().into())
     }
 
     #[test]
@@ -271,7 +597,7 @@ mod tests {
         let connect_msg = mock_ibc_channel_connect_ack(
             "channel-0",
             IBC_ORDERING,
-            IBC_APP_VERSION,
+            IbcVersion::LATEST.as_str(),
             "wasm.wrong_controller_addr",
         );
         let err = ibc_channel_connect(deps.as_mut(), env.clone(), connect_msg).unwrap_err();
@@ -287,7 +613,7 @@ mod tests {
         let connect_msg = mock_ibc_channel_connect_ack(
             GOV_CHANNEL,
             IBC_ORDERING,
-            IBC_APP_VERSION,
+            IbcVersion::LATEST.as_str(),
             &format!("wasm.{}", CONTROLLER),
         );
         ibc_channel_connect(deps.as_mut(), env.clone(), connect_msg).unwrap();
@@ -307,6 +633,12 @@ mod tests {
                 accept_new_connections: None,
                 max_signal_outage: None,
                 emergency_owner: None,
+                timelock: None,
+                guardian: None,
+                clear_guardian: None,
+                max_proposal_age: None,
+                min_astro_transfer: None,
+                astro_transfer_cooldown: None,
             }),
         )
         .unwrap();
@@ -315,7 +647,7 @@ mod tests {
         let connect_msg = mock_ibc_channel_connect_ack(
             "channel-21",
             IBC_ORDERING,
-            IBC_APP_VERSION,
+            IbcVersion::LATEST.as_str(),
             &format!("wasm.{}", CONTROLLER),
         );
         let err = ibc_channel_connect(deps.as_mut(), env.clone(), connect_msg).unwrap_err();
@@ -357,6 +689,12 @@ mod tests {
                 accept_new_connections: None,
                 max_signal_outage: None,
                 emergency_owner: None,
+                timelock: None,
+                guardian: None,
+                clear_guardian: None,
+                max_proposal_age: None,
+                min_astro_transfer: None,
+                astro_transfer_cooldown: None,
             }),
         )
         .unwrap();
@@ -388,4 +726,1172 @@ mod tests {
         let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
         assert_eq!(ack, IbcAckResult::Ok(b"ok".into()));
     }
+
+    #[test]
+    fn timelock() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info);
+        connect_gov_channel(deps.as_mut());
+        update_config(
+            deps.as_mut(),
+            UpdateConfigMsg {
+                timelock: Some(3600),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Proposals are queued instead of being executed right away
+        let proposal = SatelliteMsg::ExecuteProposal {
+            id: 2,
+            messages: vec![CosmosMsg::Custom(Empty {})],
+            sent_at: None,
+            epoch: None,
+            mode: ExecutionMode::Atomic,
+        };
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(resp.messages.is_empty());
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(ack, IbcAckResult::Ok(QUEUED_ACK.into()));
+
+        let queued: QueuedProposal = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::QueuedProposal { id: 2 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(queued.status, QueueStatus::Queued);
+        assert_eq!(queued.eta, env.block.time.plus_seconds(3600));
+
+        // Executed proposals can't be queued again
+        RESULTS.save(deps.as_mut().storage, 1, &1).unwrap();
+        let proposal = SatelliteMsg::ExecuteProposal {
+            id: 1,
+            messages: vec![],
+            sent_at: None,
+            epoch: None,
+            mode: ExecutionMode::Atomic,
+        };
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            IbcAckResult::Error(
+                AckError::new(
+                    AckErrorCode::AlreadyExecuted,
+                    ContractError::ProposalAlreadyExecuted { id: 1 }
+                )
+                .encode()
+                .unwrap()
+            )
+        );
+
+        // v1 controllers can't tell queued proposals from executed ones
+        CHANNEL_VERSIONS
+            .save(deps.as_mut().storage, GOV_CHANNEL, &IbcVersion::V1)
+            .unwrap();
+        let msg = mock_ibc_packet_recv(
+            GOV_CHANNEL,
+            &IbcProposal {
+                id: 3,
+                messages: vec![],
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env, msg).unwrap();
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            IbcAckResult::Error(
+                ContractError::TimelockNotSupported {
+                    version: IbcVersion::V1
+                }
+                .to_string()
+            )
+        );
+        assert!(!QUEUED_PROPOSALS.has(deps.as_ref().storage, 3));
+    }
+
+    #[test]
+    fn proposal_freshness() {
+        let (mut deps, mut env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+
+        // The max age is either disabled or within the limits
+        for max_proposal_age in [*PROPOSAL_AGE_LIMITS.start() - 1, u64::MAX] {
+            let err = update_config(
+                deps.as_mut(),
+                UpdateConfigMsg {
+                    max_proposal_age: Some(max_proposal_age),
+                    ..Default::default()
+                },
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::ProposalAgeLimitsError {});
+        }
+        for max_proposal_age in [0, 600] {
+            update_config(
+                deps.as_mut(),
+                UpdateConfigMsg {
+                    max_proposal_age: Some(max_proposal_age),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+
+        let sent_at = env.block.time;
+        let proposal = SatelliteMsg::ExecuteProposal {
+            id: 1,
+            messages: vec![CosmosMsg::Custom(Empty {})],
+            sent_at: Some(sent_at),
+            epoch: None,
+            mode: ExecutionMode::Atomic,
+        };
+
+        // The proposal was relayed in time
+        env.block.time = sent_at.plus_seconds(600);
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(ack, IbcAckResult::Ok(b"ok".into()));
+
+        // The proposal is too old even though the packet itself hasn't timed out
+        env.block.time = sent_at.plus_seconds(601);
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(resp.messages.is_empty());
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            IbcAckResult::Error(
+                AckError::new(
+                    AckErrorCode::StaleProposal,
+                    ContractError::ProposalExpired {
+                        id: 1,
+                        sent_at,
+                        max_age: 600
+                    }
+                )
+                .encode()
+                .unwrap()
+            )
+        );
+
+        // v1 packets don't carry the send time, thus they aren't checked
+        let msg = mock_ibc_packet_recv(
+            GOV_CHANNEL,
+            &SatelliteMsg::ExecuteProposal {
+                id: 2,
+                messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(
+            resp.acknowledgement,
+            ack_receipt(&ExecutionReceipt::new(&env.block, vec![])).unwrap()
+        );
+    }
+
+    #[test]
+    fn replay_protection() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+        RESULTS.save(deps.as_mut().storage, 1, &1).unwrap();
+
+        let proposal = |id: u64, epoch: u64| SatelliteMsg::ExecuteProposal {
+            id,
+            messages: vec![],
+            sent_at: Some(env.block.time),
+            epoch: Some(epoch),
+            mode: ExecutionMode::Atomic,
+        };
+        let error_ack = |code: AckErrorCode, err: ContractError| {
+            IbcAckResult::Error(AckError::new(code, err).encode().unwrap())
+        };
+
+        // A newer epoch supersedes the stored one
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal(2, 3)).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(
+            resp.acknowledgement,
+            ack_receipt(&ExecutionReceipt::new(&env.block, vec![])).unwrap()
+        );
+        let epoch: u64 =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::ControllerEpoch {}).unwrap())
+                .unwrap();
+        assert_eq!(epoch, 3);
+
+        // Proposals of older epochs are rejected
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal(3, 2)).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(resp.messages.is_empty());
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            error_ack(
+                AckErrorCode::SupersededEpoch,
+                ContractError::SupersededEpoch {
+                    id: 3,
+                    epoch: 2,
+                    current: 3
+                }
+            )
+        );
+
+        // Executed proposals are refused
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal(1, 3)).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            error_ack(
+                AckErrorCode::AlreadyExecuted,
+                ContractError::ProposalAlreadyExecuted { id: 1 }
+            )
+        );
+
+        // Batched proposals are checked one by one
+        let batch = SatelliteMsg::ExecuteBatch {
+            proposals: vec![
+                BatchProposal {
+                    id: 2,
+                    messages: vec![],
+                    mode: ExecutionMode::Atomic,
+                },
+                BatchProposal {
+                    id: 4,
+                    messages: vec![],
+                    mode: ExecutionMode::Atomic,
+                },
+            ],
+            sent_at: env.block.time,
+            epoch: 3,
+        };
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &batch).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(
+            resp.acknowledgement,
+            ack_batch(&[
+                BatchProposalResult {
+                    id: 2,
+                    result: error_ack(
+                        AckErrorCode::AlreadyExecuted,
+                        ContractError::ProposalAlreadyExecuted { id: 2 }
+                    ),
+                },
+                BatchProposalResult {
+                    id: 4,
+                    result: IbcAckResult::Ok(
+                        to_json_binary(&ExecutionReceipt::new(&env.block, vec![])).unwrap()
+                    ),
+                },
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn initial_epoch() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info);
+        connect_gov_channel(deps.as_mut());
+        CONTROLLER_EPOCH.save(deps.as_mut().storage, &3).unwrap();
+
+        let proposal = |id: u64, epoch: u64| SatelliteMsg::ExecuteProposal {
+            id,
+            messages: vec![],
+            sent_at: Some(env.block.time),
+            epoch: Some(epoch),
+            mode: ExecutionMode::Atomic,
+        };
+        let error_ack = |code: AckErrorCode, err: ContractError| {
+            IbcAckResult::Error(AckError::new(code, err).encode().unwrap())
+        };
+
+        // Proposals without an epoch belong to the initial one
+        let msg = mock_ibc_packet_recv(
+            GOV_CHANNEL,
+            &SatelliteMsg::ExecuteProposal {
+                id: 5,
+                messages: vec![],
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            error_ack(
+                AckErrorCode::SupersededEpoch,
+                ContractError::SupersededEpoch {
+                    id: 5,
+                    epoch: 0,
+                    current: 3
+                }
+            )
+        );
+
+        // A new controller starts over from the initial epoch
+        let new_channel = "channel-21";
+        update_config(
+            deps.as_mut(),
+            UpdateConfigMsg {
+                accept_new_connections: Some(true),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let connect_msg = mock_ibc_channel_connect_ack(
+            new_channel,
+            IBC_ORDERING,
+            IbcVersion::LATEST.as_str(),
+            &format!("wasm.{}", CONTROLLER),
+        );
+        ibc_channel_connect(deps.as_mut(), env.clone(), connect_msg).unwrap();
+        update_config(
+            deps.as_mut(),
+            UpdateConfigMsg {
+                gov_channel: Some(new_channel.to_string()),
+                accept_new_connections: Some(false),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let epoch: u64 =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::ControllerEpoch {}).unwrap())
+                .unwrap();
+        assert_eq!(epoch, 0);
+
+        let msg = mock_ibc_packet_recv(new_channel, &proposal(5, 0)).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(
+            resp.acknowledgement,
+            ack_receipt(&ExecutionReceipt::new(&env.block, vec![])).unwrap()
+        );
+    }
+
+    #[test]
+    fn message_policy() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::UpdateMessagePolicy(MessagePolicy {
+                denoms: PolicyList {
+                    allow: vec!["uastro".to_string()],
+                    deny: vec![],
+                },
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        let msg = mock_ibc_packet_recv(
+            GOV_CHANNEL,
+            &SatelliteMsg::ExecuteProposal {
+                id: 1,
+                messages: vec![
+                    CosmosMsg::Custom(Empty {}),
+                    BankMsg::Send {
+                        to_address: "receiver".to_string(),
+                        amount: coins(100, "uluna"),
+                    }
+                    .into(),
+                ],
+                sent_at: Some(env.block.time),
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env, msg).unwrap();
+
+        // The rejected message is acknowledged with its index
+        assert!(resp.messages.is_empty());
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        assert_eq!(
+            ack,
+            IbcAckResult::Error(
+                AckError {
+                    code: AckErrorCode::PolicyViolation,
+                    message_index: Some(1),
+                    error: ContractError::MessageDenied {
+                        index: 1,
+                        reason: "denom uluna is not allowed".to_string(),
+                    }
+                    .to_string(),
+                }
+                .encode()
+                .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn batch_execution() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+
+        let batch = SatelliteMsg::ExecuteBatch {
+            proposals: vec![
+                BatchProposal {
+                    id: 1,
+                    messages: vec![CosmosMsg::Custom(Empty {})],
+                    mode: ExecutionMode::Atomic,
+                },
+                BatchProposal {
+                    id: 2,
+                    messages: vec![],
+                    mode: ExecutionMode::Atomic,
+                },
+                BatchProposal {
+                    id: 3,
+                    messages: vec![CosmosMsg::Custom(Empty {})],
+                    mode: ExecutionMode::Atomic,
+                },
+            ],
+            sent_at: env.block.time,
+            epoch: 0,
+        };
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &batch).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+
+        // Proposals with messages are executed in their own submessages
+        assert_eq!(resp.messages.len(), 2);
+        for (msg, id) in resp.messages.iter().zip([1, 3]) {
+            assert_eq!(msg.id, EXECUTE_BATCHED_ID);
+            assert_eq!(msg.reply_on, ReplyOn::Always);
+            match &msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                    from_json::<ExecuteMsg>(msg).unwrap(),
+                    ExecuteMsg::RunBatched {
+                        id,
+                        messages: vec![CosmosMsg::Custom(Empty {})],
+                        mode: ExecutionMode::Atomic,
+                    }
+                ),
+                _ => panic!("Unexpected message"),
+            }
+        }
+        let empty_receipt = to_json_binary(&ExecutionReceipt::new(&env.block, vec![])).unwrap();
+        assert_eq!(
+            resp.acknowledgement,
+            ack_batch(&[BatchProposalResult {
+                id: 2,
+                result: IbcAckResult::Ok(empty_receipt.clone()),
+            }])
+            .unwrap()
+        );
+
+        // The first proposal succeeded, thus the acknowledgement is not complete yet
+        let receipt = to_json_binary(&ExecutionReceipt::new(
+            &env.block,
+            vec![MessageResult {
+                data: None,
+                events_hash: content_hash(b"[]"),
+                error: None,
+            }],
+        ))
+        .unwrap();
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: EXECUTE_BATCHED_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: execute_response_data(Some(receipt.clone())),
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(resp.data, None);
+
+        // The failure of the last proposal doesn't revert the others
+        let resp = reply(
+            deps.as_mut(),
+            env,
+            Reply {
+                id: EXECUTE_BATCHED_ID,
+                result: SubMsgResult::Err("Some error".to_string()),
+            },
+        )
+        .unwrap();
+        let ack: IbcAckResult = from_json(resp.data.unwrap()).unwrap();
+        let IbcAckResult::Ok(results) = ack else {
+            panic!("Unexpected acknowledgement")
+        };
+        assert_eq!(
+            from_json::<Vec<BatchProposalResult>>(results).unwrap(),
+            vec![
+                BatchProposalResult {
+                    id: 2,
+                    result: IbcAckResult::Ok(empty_receipt),
+                },
+                BatchProposalResult {
+                    id: 1,
+                    result: IbcAckResult::Ok(receipt),
+                },
+                BatchProposalResult {
+                    id: 3,
+                    result: IbcAckResult::Error(
+                        AckError::new(AckErrorCode::MessageFailed, "Some error")
+                            .encode()
+                            .unwrap()
+                    ),
+                },
+            ]
+        );
+        assert!(RESULTS.has(deps.as_ref().storage, 1));
+        assert!(RESULTS.has(deps.as_ref().storage, 2));
+        assert!(!RESULTS.has(deps.as_ref().storage, 3));
+        assert!(!BATCH_PENDING.exists(deps.as_ref().storage));
+    }
+
+    #[test]
+    fn chunked_proposal() {
+        let (mut deps, mut env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+
+        let messages = vec![CosmosMsg::Custom(Empty {}); 3];
+        let chunks = SatelliteMsg::chunks(
+            1,
+            &messages,
+            16,
+            env.block.time,
+            0,
+            ExecutionMode::Atomic,
+            1,
+        )
+        .unwrap();
+        assert_eq!(chunks.len(), 3);
+
+        // Chunks may arrive in any order
+        for chunk in chunks.iter().skip(1).rev() {
+            let msg = mock_ibc_packet_recv(GOV_CHANNEL, chunk).unwrap();
+            let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+            assert!(resp.messages.is_empty());
+            assert_eq!(resp.acknowledgement, ack_chunk());
+        }
+        let upload: Option<ChunkedUpload> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ChunkedUpload { id: 1 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(upload.unwrap().received, 2);
+
+        // The proposal is executed once every chunk has arrived
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &chunks[0]).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        assert_eq!(resp.messages[0].id, EXECUTE_PROPOSAL_ID);
+        assert_eq!(resp.acknowledgement, ack_ok());
+        assert!(!UPLOADS.has(deps.as_ref().storage, 1));
+
+        // Corrupted content is rejected
+        let mut chunks = SatelliteMsg::chunks(
+            2,
+            &messages,
+            16,
+            env.block.time,
+            0,
+            ExecutionMode::Atomic,
+            1,
+        )
+        .unwrap();
+        if let SatelliteMsg::ExecuteChunk { data, .. } = &mut chunks[1] {
+            *data = Binary::from(b"corrupted".as_slice());
+        }
+        let mut ack = Binary::default();
+        for chunk in &chunks {
+            let msg = mock_ibc_packet_recv(GOV_CHANNEL, chunk).unwrap();
+            ack = ibc_packet_receive(deps.as_mut(), env.clone(), msg)
+                .unwrap()
+                .acknowledgement;
+        }
+        assert_eq!(
+            ack,
+            ack_error(
+                IbcVersion::LATEST,
+                &AckError::new(
+                    AckErrorCode::InvalidChunk,
+                    ContractError::ChunkHashMismatch { id: 2 }
+                )
+            )
+            .unwrap()
+        );
+        assert!(!UPLOADS.has(deps.as_ref().storage, 2));
+
+        // Abandoned uploads expire
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &chunks[0]).unwrap();
+        ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let clear_msg = ExecuteMsg::ClearExpiredUpload { id: 2 };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            clear_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UploadNotExpired {
+                id: 2,
+                expires_at: env.block.time.plus_seconds(UPLOAD_TTL)
+            }
+        );
+
+        env.block.time = env.block.time.plus_seconds(UPLOAD_TTL + 1);
+        execute(deps.as_mut(), env, mock_info("keeper", &[]), clear_msg).unwrap();
+        assert!(!UPLOADS.has(deps.as_ref().storage, 2));
+        assert!(UPLOAD_CHUNKS
+            .prefix(2)
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn proposal_simulation() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+
+        let messages = vec![CosmosMsg::Custom(Empty {})];
+        let msg = mock_ibc_packet_recv(
+            GOV_CHANNEL,
+            &SatelliteMsg::SimulateProposal {
+                id: 1,
+                messages: messages.clone(),
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        assert_eq!(resp.messages[0].id, SIMULATION_ID);
+        assert_eq!(resp.messages[0].reply_on, ReplyOn::Always);
+
+        // Only the contract itself can run simulations
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::RunSimulation {
+                id: 1,
+                messages: messages.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(env.contract.address.as_str(), &[]),
+            ExecuteMsg::RunSimulation { id: 1, messages },
+        )
+        .unwrap();
+        assert_eq!(resp.messages[0].id, SIMULATION_STEP_ID);
+        match &resp.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                from_json::<ExecuteMsg>(msg).unwrap(),
+                ExecuteMsg::RunBatched {
+                    id: 1,
+                    messages: vec![CosmosMsg::Custom(Empty {})],
+                    mode: ExecutionMode::Atomic,
+                }
+            ),
+            _ => panic!("Unexpected message"),
+        }
+
+        // Passed messages are reverted by failing the simulation step
+        let err = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: SIMULATION_STEP_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MessagesCheckPassed {});
+
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: SIMULATION_ID,
+                result: SubMsgResult::Err(err.to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            resp.data.unwrap(),
+            to_json_binary(&IbcAckResult::Ok(
+                to_json_binary(&SimulationResult::Passed {}).unwrap()
+            ))
+            .unwrap()
+        );
+
+        // Failed messages are reported back
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: SIMULATION_STEP_ID,
+                result: SubMsgResult::Err("Some error".to_string()),
+            },
+        )
+        .unwrap();
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: SIMULATION_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: execute_response_data(resp.data),
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            resp.data.unwrap(),
+            to_json_binary(&IbcAckResult::Ok(
+                to_json_binary(&SimulationResult::Failed {
+                    error: "Some error".to_string()
+                })
+                .unwrap()
+            ))
+            .unwrap()
+        );
+
+        // A missing result is acknowledged as an error rather than failing the packet
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: SIMULATION_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        let ack: IbcAckResult = from_json(resp.data.unwrap()).unwrap();
+        assert_eq!(
+            ack,
+            IbcAckResult::Error(
+                AckError::new(AckErrorCode::Unknown, "Simulation result is missing")
+                    .encode()
+                    .unwrap()
+            )
+        );
+
+        // Nothing is stored by simulations
+        assert!(RESULTS
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .next()
+            .is_none());
+
+        // Messages are checked against the policy the same way as in executions
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::UpdateMessagePolicy(MessagePolicy {
+                kinds: PolicyList {
+                    allow: vec![],
+                    deny: vec![MessageKind::Custom],
+                },
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+        let msg = mock_ibc_packet_recv(
+            GOV_CHANNEL,
+            &SatelliteMsg::SimulateProposal {
+                id: 2,
+                messages: vec![CosmosMsg::Custom(Empty {})],
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env, msg).unwrap();
+        assert!(resp.messages.is_empty());
+        let ack: IbcAckResult = from_json(&resp.acknowledgement).unwrap();
+        match ack {
+            IbcAckResult::Error(error) => {
+                assert_eq!(AckError::decode(&error).code, AckErrorCode::PolicyViolation)
+            }
+            _ => panic!("Expected an error acknowledgement"),
+        }
+    }
+
+    #[test]
+    fn execution_receipt() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+
+        let msg = mock_ibc_packet_recv(
+            GOV_CHANNEL,
+            &SatelliteMsg::ExecuteProposal {
+                id: 1,
+                messages: vec![CosmosMsg::Custom(Empty {}), CosmosMsg::Custom(Empty {})],
+                sent_at: Some(env.block.time),
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+        )
+        .unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+
+        // The proposal is executed in a submessage
+        assert_eq!(resp.messages.len(), 1);
+        assert_eq!(resp.messages[0].id, EXECUTE_PROPOSAL_ID);
+        assert_eq!(resp.messages[0].reply_on, ReplyOn::Always);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(env.contract.address.as_str(), &[]),
+            ExecuteMsg::RunBatched {
+                id: 1,
+                messages: vec![CosmosMsg::Custom(Empty {}), CosmosMsg::Custom(Empty {})],
+                mode: ExecutionMode::Atomic,
+            },
+        )
+        .unwrap();
+
+        // Every message replies with its result
+        assert_eq!(resp.messages.len(), 2);
+        for msg in &resp.messages {
+            assert_eq!(msg.id, RECEIVE_ID);
+            assert_eq!(msg.reply_on, ReplyOn::Success);
+        }
+
+        let events = vec![Event::new("wasm").add_attribute("action", "some_action")];
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: RECEIVE_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: events.clone(),
+                    data: Some(b"data".into()),
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(resp.data, None);
+        assert!(!RESULTS.has(deps.as_ref().storage, 1));
+
+        // The last reply returns the receipt
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: RECEIVE_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        let receipt = ExecutionReceipt {
+            height: env.block.height,
+            time: env.block.time,
+            messages: vec![
+                MessageResult {
+                    data: Some(b"data".into()),
+                    events_hash: content_hash(&to_json_vec(&events).unwrap()),
+                    error: None,
+                },
+                MessageResult {
+                    data: None,
+                    events_hash: content_hash(b"[]"),
+                    error: None,
+                },
+            ],
+        };
+        assert_eq!(resp.data, Some(to_json_binary(&receipt).unwrap()));
+        assert!(RESULTS.has(deps.as_ref().storage, 1));
+        assert!(!EXECUTION_PROGRESS.exists(deps.as_ref().storage));
+
+        // The receipt overrides the packet acknowledgement
+        let resp = reply(
+            deps.as_mut(),
+            env,
+            Reply {
+                id: EXECUTE_PROPOSAL_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: execute_response_data(resp.data),
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(resp.data.unwrap(), ack_receipt(&receipt).unwrap());
+        assert!(!PENDING_PROPOSAL.exists(deps.as_ref().storage));
+    }
+
+    #[test]
+    fn best_effort_execution() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+
+        let messages = vec![CosmosMsg::Custom(Empty {}), CosmosMsg::Custom(Empty {})];
+        let proposal = SatelliteMsg::ExecuteProposal {
+            id: 1,
+            messages: messages.clone(),
+            sent_at: Some(env.block.time),
+            epoch: None,
+            mode: ExecutionMode::BestEffort,
+        };
+
+        // Best-effort proposals can't be sent over v1 channels
+        assert!(proposal.encode(IbcVersion::V1).is_err());
+
+        let msg = mock_ibc_packet_recv(GOV_CHANNEL, &proposal).unwrap();
+        let resp = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        assert_eq!(resp.messages[0].id, EXECUTE_PROPOSAL_ID);
+        match &resp.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                from_json::<ExecuteMsg>(msg).unwrap(),
+                ExecuteMsg::RunBatched {
+                    id: 1,
+                    messages: messages.clone(),
+                    mode: ExecutionMode::BestEffort,
+                }
+            ),
+            _ => panic!("Unexpected message"),
+        }
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(env.contract.address.as_str(), &[]),
+            ExecuteMsg::RunBatched {
+                id: 1,
+                messages,
+                mode: ExecutionMode::BestEffort,
+            },
+        )
+        .unwrap();
+
+        // Every message replies with its result, including failures
+        assert_eq!(resp.messages.len(), 2);
+        for msg in &resp.messages {
+            assert_eq!(msg.id, RECEIVE_ID);
+            assert_eq!(msg.reply_on, ReplyOn::Always);
+        }
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: RECEIVE_ID,
+                result: SubMsgResult::Err("Some error".to_string()),
+            },
+        )
+        .unwrap();
+        let resp = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: RECEIVE_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        // The receipt lists the failed message while the rest of the proposal is kept
+        let results = vec![
+            MessageResult::failed("Some error"),
+            MessageResult {
+                data: None,
+                events_hash: content_hash(b"[]"),
+                error: None,
+            },
+        ];
+        let receipt = ExecutionReceipt::new(&env.block, results.clone());
+        assert_eq!(receipt.failed_messages(), vec![0]);
+        assert_eq!(resp.data, Some(to_json_binary(&receipt).unwrap()));
+        assert_eq!(resp.attributes, vec![attr("failed_messages", "0")]);
+        assert!(RESULTS.has(deps.as_ref().storage, 1));
+
+        let stored: Vec<MessageResult> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::MessageResults { id: 1 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored, results);
+
+        // The partial success is acknowledged with the receipt
+        let resp = reply(
+            deps.as_mut(),
+            env,
+            Reply {
+                id: EXECUTE_PROPOSAL_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: execute_response_data(resp.data),
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(resp.data.unwrap(), ack_receipt(&receipt).unwrap());
+    }
+
+    #[test]
+    fn failed_message_index() {
+        let (mut deps, env, info) = mock_all(OWNER);
+        init_contract(deps.as_mut(), env.clone(), info.clone());
+
+        connect_gov_channel(deps.as_mut());
+
+        let messages: Vec<CosmosMsg> = (0..=MAX_PROBED_MESSAGES)
+            .map(|i| {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: format!("contract{i}"),
+                    msg: b"{}".into(),
+                    funds: vec![],
+                })
+            })
+            .collect();
+        let probed_messages = |resp: &Response| match &resp.messages[..] {
+            [SubMsg {
+                id: PROBE_ID,
+                msg: CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }),
+                ..
+            }] => match from_json(msg).unwrap() {
+                ExecuteMsg::<Empty>::RunSimulation { messages, .. } => messages,
+                _ => panic!("Unexpected message"),
+            },
+            _ => panic!("Unexpected messages"),
+        };
+        let receive_failed = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                              id: u64,
+                              messages: &[CosmosMsg]| {
+            let msg = mock_ibc_packet_recv(
+                GOV_CHANNEL,
+                &SatelliteMsg::ExecuteProposal {
+                    id,
+                    messages: messages.to_vec(),
+                    sent_at: Some(env.block.time),
+                    epoch: None,
+                    mode: ExecutionMode::Atomic,
+                },
+            )
+            .unwrap();
+            ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+            reply(
+                deps.as_mut(),
+                env.clone(),
+                Reply {
+                    id: EXECUTE_PROPOSAL_ID,
+                    result: SubMsgResult::Err("codespace: wasm, code: 5".to_string()),
+                },
+            )
+            .unwrap()
+        };
+
+        // Submessage errors are redacted, thus the failing message is located by
+        // bisecting the proposal
+        for failing_index in 0..5 {
+            let id = failing_index as u64 + 1;
+            let mut resp = receive_failed(&mut deps, id, &messages[..5]);
+            let mut probes = 0;
+            while !resp.messages.is_empty() {
+                probes += 1;
+                let result = if probed_messages(&resp).len() > failing_index {
+                    SubMsgResult::Ok(SubMsgResponse {
+                        events: vec![],
+                        data: None,
+                    })
+                } else {
+                    SubMsgResult::Err(ContractError::MessagesCheckPassed {}.to_string())
+                };
+                resp = reply(
+                    deps.as_mut(),
+                    env.clone(),
+                    Reply {
+                        id: PROBE_ID,
+                        result,
+                    },
+                )
+                .unwrap();
+            }
+
+            assert!(probes <= 3);
+            assert_eq!(
+                resp.data.unwrap(),
+                ack_error(
+                    IbcVersion::LATEST,
+                    &AckError::message_failed(failing_index as u32, "codespace: wasm, code: 5")
+                )
+                .unwrap()
+            );
+            assert!(!FAILURE_PROBE.exists(deps.as_ref().storage));
+            assert!(!RESULTS.has(deps.as_ref().storage, id));
+        }
+
+        // Large proposals are not probed
+        let resp = receive_failed(&mut deps, 6, &messages);
+        assert!(resp.messages.is_empty());
+        assert_eq!(
+            resp.data.unwrap(),
+            ack_error(
+                IbcVersion::LATEST,
+                &AckError::new(AckErrorCode::MessageFailed, "codespace: wasm, code: 5")
+            )
+            .unwrap()
+        );
+        assert!(!FAILURE_PROBE.exists(deps.as_ref().storage));
+    }
 }
@@ -1,4 +1,9 @@
 pub mod contract;
 pub mod error;
 pub mod ibc;
+mod migration;
+pub mod policy;
+pub mod stargate;
 pub mod state;
+#[cfg(test)]
+mod test_utils;
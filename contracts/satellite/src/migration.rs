@@ -0,0 +1,46 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, StdResult, Storage, Uint128};
+use cw_storage_plus::Item;
+
+use crate::state::{Config, CONFIG};
+
+/// Config layout used by astro-satellite v1.2.x
+#[cw_serde]
+struct ConfigV120 {
+    owner: Addr,
+    max_signal_outage: u64,
+    emergency_owner: Addr,
+    astro_denom: String,
+    main_controller_port: String,
+    main_maker: String,
+    gov_channel: Option<String>,
+    transfer_channel: String,
+    timeout: u64,
+}
+
+const CONFIG_V120: Item<ConfigV120> = Item::new("config");
+
+/// Fills the parameters introduced after v1.2.x with their default values
+pub fn migrate_config(storage: &mut dyn Storage) -> StdResult<()> {
+    let old_config = CONFIG_V120.load(storage)?;
+
+    CONFIG.save(
+        storage,
+        &Config {
+            owner: old_config.owner,
+            max_signal_outage: old_config.max_signal_outage,
+            emergency_owner: old_config.emergency_owner,
+            astro_denom: old_config.astro_denom,
+            main_controller_port: old_config.main_controller_port,
+            main_maker: old_config.main_maker,
+            gov_channel: old_config.gov_channel,
+            transfer_channel: old_config.transfer_channel,
+            timeout: old_config.timeout,
+            timelock: 0,
+            guardian: None,
+            max_proposal_age: 0,
+            min_astro_transfer: Uint128::zero(),
+            astro_transfer_cooldown: 0,
+        },
+    )
+}
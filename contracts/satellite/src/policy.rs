@@ -0,0 +1,138 @@
+use cosmwasm_std::{
+    BankMsg, Coin, CosmosMsg, DepsMut, IbcMsg, MessageInfo, Response, StakingMsg, StdResult,
+    Storage, WasmMsg,
+};
+
+use astro_satellite_package::{MessageKind, MessagePolicy};
+
+use crate::error::ContractError;
+use crate::stargate::{decode_stargate, MAX_NESTING_DEPTH};
+use crate::state::{CONFIG, MESSAGE_POLICY};
+
+/// Returns the active message policy. The satellite allows every message until the policy is set
+pub fn load_policy(storage: &dyn Storage) -> StdResult<MessagePolicy> {
+    Ok(MESSAGE_POLICY.may_load(storage)?.unwrap_or_default())
+}
+
+/// Replaces the message policy. Only the owner can update it
+pub fn update_message_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    mut policy: MessagePolicy,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Contracts are compared with the addresses of the messages as is, thus they are normalized
+    for contract in policy
+        .contracts
+        .allow
+        .iter_mut()
+        .chain(policy.contracts.deny.iter_mut())
+    {
+        *contract = deps.api.addr_validate(contract)?.to_string();
+    }
+    MESSAGE_POLICY.save(deps.storage, &policy)?;
+
+    Ok(Response::new().add_attribute("action", "update_message_policy"))
+}
+
+/// Checks that every message is allowed by the policy
+pub fn check_policy<M>(
+    policy: &MessagePolicy,
+    messages: &[CosmosMsg<M>],
+) -> Result<(), ContractError> {
+    messages.iter().enumerate().try_for_each(|(index, msg)| {
+        check_message(policy, msg, 0).map_err(|reason| ContractError::MessageDenied {
+            index: index as u32,
+            reason,
+        })
+    })
+}
+
+/// Returns the reason the message is rejected for if any.
+/// Messages wrapped by well-known Stargate messages are checked as if they were sent directly
+fn check_message<M>(policy: &MessagePolicy, msg: &CosmosMsg<M>, depth: u32) -> Result<(), String> {
+    let kind = message_kind(msg).ok_or("unsupported message kind")?;
+    if !policy.kinds.permits(&kind) {
+        return Err(format!("{kind} messages are not allowed"));
+    }
+
+    if let CosmosMsg::Stargate { type_url, value } = msg {
+        if !policy.type_urls.permits(type_url) {
+            return Err(format!("type url {type_url} is not allowed"));
+        }
+
+        let wrapped = decode_stargate::<M>(type_url, value).map_err(|err| err.to_string())?;
+        if let Some(wrapped) = wrapped {
+            if depth >= MAX_NESTING_DEPTH {
+                return Err(format!(
+                    "messages nested deeper than {MAX_NESTING_DEPTH} levels are not allowed"
+                ));
+            }
+            wrapped
+                .iter()
+                .try_for_each(|msg| check_message(policy, msg, depth + 1))?;
+        }
+    }
+
+    if let Some(contract) = message_contract(msg) {
+        if !policy.contracts.permits(contract) {
+            return Err(format!("contract {contract} is not allowed"));
+        }
+    }
+
+    message_funds(msg).iter().try_for_each(|coin| {
+        if policy.denoms.permits(&coin.denom) {
+            Ok(())
+        } else {
+            Err(format!("denom {} is not allowed", coin.denom))
+        }
+    })
+}
+
+fn message_kind<M>(msg: &CosmosMsg<M>) -> Option<MessageKind> {
+    match msg {
+        CosmosMsg::Bank(_) => Some(MessageKind::Bank),
+        CosmosMsg::Custom(_) => Some(MessageKind::Custom),
+        CosmosMsg::Staking(_) => Some(MessageKind::Staking),
+        CosmosMsg::Distribution(_) => Some(MessageKind::Distribution),
+        CosmosMsg::Stargate { .. } => Some(MessageKind::Stargate),
+        CosmosMsg::Ibc(_) => Some(MessageKind::Ibc),
+        CosmosMsg::Wasm(_) => Some(MessageKind::Wasm),
+        CosmosMsg::Gov(_) => Some(MessageKind::Gov),
+        _ => None,
+    }
+}
+
+/// Returns the contract executed, migrated or administered by the wasm message
+fn message_contract<M>(msg: &CosmosMsg<M>) -> Option<&String> {
+    match msg {
+        CosmosMsg::Wasm(
+            WasmMsg::Execute { contract_addr, .. }
+            | WasmMsg::Migrate { contract_addr, .. }
+            | WasmMsg::UpdateAdmin { contract_addr, .. }
+            | WasmMsg::ClearAdmin { contract_addr },
+        ) => Some(contract_addr),
+        _ => None,
+    }
+}
+
+/// Returns the funds sent, burned or delegated by the message
+fn message_funds<M>(msg: &CosmosMsg<M>) -> &[Coin] {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. } | BankMsg::Burn { amount }) => amount,
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. } | WasmMsg::Instantiate { funds, .. }) => {
+            funds
+        }
+        CosmosMsg::Ibc(IbcMsg::Transfer { amount, .. })
+        | CosmosMsg::Staking(
+            StakingMsg::Delegate { amount, .. }
+            | StakingMsg::Undelegate { amount, .. }
+            | StakingMsg::Redelegate { amount, .. },
+        ) => std::slice::from_ref(amount),
+        _ => &[],
+    }
+}
@@ -0,0 +1,191 @@
+use cosmos_sdk_proto::cosmos::authz::v1beta1::MsgExec;
+use cosmos_sdk_proto::cosmos::bank::v1beta1::MsgSend;
+use cosmos_sdk_proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+use cosmos_sdk_proto::cosmos::gov::v1beta1::MsgSubmitProposal as MsgSubmitProposalV1Beta1;
+use cosmos_sdk_proto::cosmwasm::wasm::v1::{
+    ClearAdminProposal, MigrateContractProposal, MsgClearAdmin, MsgExecuteContract,
+    MsgMigrateContract, MsgUpdateAdmin, UpdateAdminProposal,
+};
+use cosmos_sdk_proto::Any;
+use cosmwasm_std::{BankMsg, Coin, CosmosMsg, StdError, StdResult, Timestamp, WasmMsg};
+use prost::Message;
+
+/// How deep Stargate messages wrapping other messages are inspected
+pub const MAX_NESTING_DEPTH: u32 = 8;
+
+/// `cosmos.gov.v1.MsgSubmitProposal` reduced to the proposal messages.
+/// The gov v1 types aren't exported by cosmos-sdk-proto
+#[derive(Clone, PartialEq, Message)]
+struct MsgSubmitProposalV1 {
+    #[prost(message, repeated, tag = "1")]
+    messages: Vec<Any>,
+}
+
+/// `cosmos.gov.v1.MsgExecLegacyContent` reduced to the legacy proposal content
+#[derive(Clone, PartialEq, Message)]
+struct MsgExecLegacyContent {
+    #[prost(message, optional, tag = "1")]
+    content: Option<Any>,
+}
+
+/// `ibc.applications.transfer.v1.MsgTransfer` without the timeout height.
+/// Used instead of `IbcMsg::Transfer` which can't carry a memo
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    pub(crate) source_port: String,
+    #[prost(string, tag = "2")]
+    pub(crate) source_channel: String,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) token: Option<ProtoCoin>,
+    #[prost(string, tag = "4")]
+    pub(crate) sender: String,
+    #[prost(string, tag = "5")]
+    pub(crate) receiver: String,
+    #[prost(uint64, tag = "7")]
+    pub(crate) timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    pub(crate) memo: String,
+}
+
+/// Builds the ICS-20 transfer with the memo over the transfer port
+pub fn ibc_transfer_with_memo<M>(
+    sender: String,
+    channel_id: String,
+    receiver: String,
+    amount: Coin,
+    timeout: Timestamp,
+    memo: String,
+) -> CosmosMsg<M> {
+    let msg = MsgTransfer {
+        source_port: "transfer".to_string(),
+        source_channel: channel_id,
+        token: Some(ProtoCoin {
+            denom: amount.denom,
+            amount: amount.amount.to_string(),
+        }),
+        sender,
+        receiver,
+        timeout_timestamp: timeout.nanos(),
+        memo,
+    };
+
+    CosmosMsg::Stargate {
+        type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_string(),
+        value: msg.encode_to_vec().into(),
+    }
+}
+
+/// Decodes the well-known Stargate message into the messages it wraps.
+/// Wasm and bank messages and legacy wasm proposals are converted to the equivalent
+/// `WasmMsg` and `BankMsg`. Returns `None` if the message type isn't inspected
+pub fn decode_stargate<M>(type_url: &str, value: &[u8]) -> StdResult<Option<Vec<CosmosMsg<M>>>> {
+    let messages = match type_url {
+        "/cosmos.authz.v1beta1.MsgExec" => decode::<MsgExec>(type_url, value)?
+            .msgs
+            .into_iter()
+            .map(wrapped)
+            .collect(),
+        "/cosmos.gov.v1beta1.MsgSubmitProposal" => {
+            decode::<MsgSubmitProposalV1Beta1>(type_url, value)?
+                .content
+                .into_iter()
+                .map(wrapped)
+                .collect()
+        }
+        "/cosmos.gov.v1.MsgSubmitProposal" => decode::<MsgSubmitProposalV1>(type_url, value)?
+            .messages
+            .into_iter()
+            .map(wrapped)
+            .collect(),
+        "/cosmos.gov.v1.MsgExecLegacyContent" => decode::<MsgExecLegacyContent>(type_url, value)?
+            .content
+            .into_iter()
+            .map(wrapped)
+            .collect(),
+        "/cosmwasm.wasm.v1.MsgExecuteContract" => {
+            let msg = decode::<MsgExecuteContract>(type_url, value)?;
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: msg.contract,
+                msg: msg.msg.into(),
+                funds: coins(msg.funds)?,
+            })]
+        }
+        "/cosmos.bank.v1beta1.MsgSend" => {
+            let msg = decode::<MsgSend>(type_url, value)?;
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: msg.to_address,
+                amount: coins(msg.amount)?,
+            })]
+        }
+        "/cosmwasm.wasm.v1.MsgMigrateContract" => {
+            let msg = decode::<MsgMigrateContract>(type_url, value)?;
+            vec![migrate(msg.contract, msg.code_id, msg.msg)]
+        }
+        "/cosmwasm.wasm.v1.MigrateContractProposal" => {
+            let proposal = decode::<MigrateContractProposal>(type_url, value)?;
+            vec![migrate(proposal.contract, proposal.code_id, proposal.msg)]
+        }
+        "/cosmwasm.wasm.v1.MsgUpdateAdmin" => {
+            let msg = decode::<MsgUpdateAdmin>(type_url, value)?;
+            vec![update_admin(msg.contract, msg.new_admin)]
+        }
+        "/cosmwasm.wasm.v1.UpdateAdminProposal" => {
+            let proposal = decode::<UpdateAdminProposal>(type_url, value)?;
+            vec![update_admin(proposal.contract, proposal.new_admin)]
+        }
+        "/cosmwasm.wasm.v1.MsgClearAdmin" => {
+            let msg = decode::<MsgClearAdmin>(type_url, value)?;
+            vec![clear_admin(msg.contract)]
+        }
+        "/cosmwasm.wasm.v1.ClearAdminProposal" => {
+            let proposal = decode::<ClearAdminProposal>(type_url, value)?;
+            vec![clear_admin(proposal.contract)]
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(messages))
+}
+
+fn decode<T: Message + Default>(type_url: &str, value: &[u8]) -> StdResult<T> {
+    T::decode(value).map_err(|err| StdError::parse_err(type_url, err))
+}
+
+fn coins(coins: Vec<ProtoCoin>) -> StdResult<Vec<Coin>> {
+    coins
+        .into_iter()
+        .map(|coin| {
+            Ok(Coin {
+                denom: coin.denom,
+                amount: coin.amount.parse()?,
+            })
+        })
+        .collect()
+}
+
+fn wrapped<M>(any: Any) -> CosmosMsg<M> {
+    CosmosMsg::Stargate {
+        type_url: any.type_url,
+        value: any.value.into(),
+    }
+}
+
+fn migrate<M>(contract_addr: String, new_code_id: u64, msg: Vec<u8>) -> CosmosMsg<M> {
+    CosmosMsg::Wasm(WasmMsg::Migrate {
+        contract_addr,
+        new_code_id,
+        msg: msg.into(),
+    })
+}
+
+fn update_admin<M>(contract_addr: String, admin: String) -> CosmosMsg<M> {
+    CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+        contract_addr,
+        admin,
+    })
+}
+
+fn clear_admin<M>(contract_addr: String) -> CosmosMsg<M> {
+    CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr })
+}
@@ -1,16 +1,40 @@
+use std::ops::RangeInclusive;
+
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    Addr, Api, CustomMsg, CustomQuery, DepsMut, Env, MessageInfo, Response, StdResult, Timestamp,
-    WasmMsg,
+    Addr, Api, Binary, CosmosMsg, CustomMsg, CustomQuery, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, Storage, Timestamp, Uint128, WasmMsg,
 };
 use cw_storage_plus::{Item, Map};
 
-use astro_satellite_package::{InstantiateMsg, UpdateConfigMsg};
+use astro_satellite_package::{
+    BatchProposal, BatchProposalResult, ChunkedUpload, ConfigResponse, ConfigSnapshot,
+    ExecutionMode, IbcVersion, InstantiateMsg, MessagePolicy, MessageResult, QueuedProposal,
+    UpdateConfigMsg,
+};
 use astroport::common::OwnershipProposal;
 use astroport_ibc::{SIGNAL_OUTAGE_LIMITS, TIMEOUT_LIMITS};
 
 use crate::error::ContractError;
 
+/// The longest delay the satellite timelock can be set to (2 weeks)
+pub const MAX_TIMELOCK: u64 = 1209600;
+
+/// The longest cooldown between ASTRO transfers to the hub (1 week)
+pub const MAX_ASTRO_TRANSFER_COOLDOWN: u64 = 604800;
+
+/// Max proposal age limits unless the check is disabled.
+/// Proposals can't be required to arrive faster than the shortest controller packet timeout,
+/// and the age can't exceed 2 weeks
+pub const PROPOSAL_AGE_LIMITS: RangeInclusive<u64> = *TIMEOUT_LIMITS.start()..=1209600;
+
+/// Time in seconds after which an incomplete chunked proposal upload is abandoned (1 day)
+pub const UPLOAD_TTL: u64 = 86400;
+
+/// Time in seconds after which a report of the same kind can be sent again by anyone but the owner
+/// (1 hour)
+pub const REPORT_INTERVAL: u64 = 3600;
+
 #[cw_serde]
 pub struct Config {
     /// Address which is able to update contracts' parameters
@@ -31,9 +55,54 @@ pub struct Config {
     pub transfer_channel: String,
     /// when packet times out, measured on remote chain
     pub timeout: u64,
+    /// Delay in seconds between the proposal receipt and its execution. 0 disables the timelock
+    pub timelock: u64,
+    /// Address which is able to veto queued proposals along with the emergency owner
+    pub guardian: Option<Addr>,
+    /// Time in seconds after which a proposal sent by the hub is rejected. 0 disables the check
+    pub max_proposal_age: u64,
+    /// The smallest amount of ASTRO which can be transferred to the hub
+    pub min_astro_transfer: Uint128,
+    /// Time in seconds between ASTRO transfers to the hub. 0 disables the cooldown
+    pub astro_transfer_cooldown: u64,
 }
 
 impl Config {
+    /// Returns the config as reported to the controller
+    pub fn snapshot(&self, time: Timestamp) -> ConfigSnapshot {
+        ConfigSnapshot {
+            time,
+            owner: self.owner.clone(),
+            emergency_owner: self.emergency_owner.clone(),
+            max_signal_outage: self.max_signal_outage,
+            astro_denom: self.astro_denom.clone(),
+            main_controller_port: self.main_controller_port.clone(),
+            main_maker: self.main_maker.clone(),
+            gov_channel: self.gov_channel.clone(),
+            transfer_channel: self.transfer_channel.clone(),
+            timeout: self.timeout,
+        }
+    }
+
+    pub fn to_response(&self) -> ConfigResponse {
+        ConfigResponse {
+            owner: self.owner.clone(),
+            emergency_owner: self.emergency_owner.clone(),
+            max_signal_outage: self.max_signal_outage,
+            astro_denom: self.astro_denom.clone(),
+            main_controller_port: self.main_controller_port.clone(),
+            main_maker: self.main_maker.clone(),
+            gov_channel: self.gov_channel.clone(),
+            transfer_channel: self.transfer_channel.clone(),
+            timeout: self.timeout,
+            timelock: self.timelock,
+            guardian: self.guardian.clone(),
+            max_proposal_age: self.max_proposal_age,
+            min_astro_transfer: self.min_astro_transfer,
+            astro_transfer_cooldown: self.astro_transfer_cooldown,
+        }
+    }
+
     pub(crate) fn update(
         &mut self,
         api: &dyn Api,
@@ -89,6 +158,39 @@ impl Config {
             self.emergency_owner = api.addr_validate(&emergency_owner)?;
         }
 
+        if let Some(timelock) = params.timelock {
+            if timelock > MAX_TIMELOCK {
+                return Err(ContractError::TimelockLimitsError {});
+            }
+            self.timelock = timelock;
+        }
+
+        if let Some(guardian) = params.guardian {
+            self.guardian = Some(api.addr_validate(&guardian)?);
+        }
+
+        if params.clear_guardian.unwrap_or_default() {
+            self.guardian = None;
+        }
+
+        if let Some(max_proposal_age) = params.max_proposal_age {
+            if max_proposal_age > 0 && !PROPOSAL_AGE_LIMITS.contains(&max_proposal_age) {
+                return Err(ContractError::ProposalAgeLimitsError {});
+            }
+            self.max_proposal_age = max_proposal_age;
+        }
+
+        if let Some(min_astro_transfer) = params.min_astro_transfer {
+            self.min_astro_transfer = min_astro_transfer;
+        }
+
+        if let Some(astro_transfer_cooldown) = params.astro_transfer_cooldown {
+            if astro_transfer_cooldown > MAX_ASTRO_TRANSFER_COOLDOWN {
+                return Err(ContractError::AstroTransferCooldownLimitsError {});
+            }
+            self.astro_transfer_cooldown = astro_transfer_cooldown;
+        }
+
         Ok(())
     }
 }
@@ -121,6 +223,11 @@ where
             timeout: msg.timeout,
             max_signal_outage: msg.max_signal_outage,
             emergency_owner: deps.api.addr_validate(&msg.emergency_owner)?,
+            timelock: 0,
+            guardian: None,
+            max_proposal_age: 0,
+            min_astro_transfer: Uint128::zero(),
+            astro_transfer_cooldown: 0,
         },
     )?;
 
@@ -147,8 +254,15 @@ where
     {
         return Err(ContractError::Unauthorized {});
     }
+    let gov_channel = config.gov_channel.clone();
     config.update(deps.api, params)?;
 
+    // Epochs are counted by the controller on the other end of the governance channel,
+    // thus a new controller starts over from the initial epoch
+    if config.gov_channel != gov_channel {
+        CONTROLLER_EPOCH.remove(deps.storage);
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
@@ -185,12 +299,84 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// Can be considered as a flag to check that proposal was executed.
 pub const RESULTS: Map<u64, u64> = Map::new("results");
 
+/// Stores map (proposal id, message index) -> result of the message of the best-effort proposal.
+/// Complements `RESULTS` with the messages which were reverted
+pub const MESSAGE_RESULTS: Map<(u64, u32), MessageResult> = Map::new("message_results");
+
 /// Stores data for reply endpoint.
 pub const REPLY_DATA: Item<u64> = Item::new("reply_data");
 
+/// Proposal messages being executed along with the results collected so far
+#[cw_serde]
+pub struct ExecutionProgress {
+    /// Number of the proposal messages
+    pub total: u32,
+    pub results: Vec<MessageResult>,
+    pub mode: ExecutionMode,
+}
+
+/// Results of the proposal messages executed by the current packet
+pub const EXECUTION_PROGRESS: Item<ExecutionProgress> = Item::new("execution_progress");
+
+/// Proposal executed by the current packet. Kept to locate the failing message
+pub const PENDING_PROPOSAL: Item<BatchProposal> = Item::new("pending_proposal");
+
+/// Locates the failing message of the proposal by a binary search over its prefixes
+#[cw_serde]
+pub struct FailureProbe {
+    pub id: u64,
+    pub messages: Vec<CosmosMsg>,
+    /// Length of the longest prefix known to pass
+    pub passed: u32,
+    /// Length of the shortest prefix known to fail
+    pub failed: u32,
+    /// Error the whole proposal failed with
+    pub error: String,
+}
+
+impl FailureProbe {
+    /// Length of the prefix simulated next
+    pub fn prefix_len(&self) -> u32 {
+        (self.passed + self.failed) / 2
+    }
+}
+
+pub const FAILURE_PROBE: Item<FailureProbe> = Item::new("failure_probe");
+
 /// Contains a proposal to change contract ownership.
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// Stores map channel id -> protocol version negotiated on the channel opening
+pub const CHANNEL_VERSIONS: Map<&str, IbcVersion> = Map::new("channel_versions");
+
+/// Stores map proposal id -> proposal queued by the timelock
+pub const QUEUED_PROPOSALS: Map<u64, QueuedProposal> = Map::new("queued_proposals");
+
+/// Contains ids of the batched proposals awaiting their execution replies in execution order
+pub const BATCH_PENDING: Item<Vec<u64>> = Item::new("batch_pending");
+
+/// Contains results of the batch being executed
+pub const BATCH_RESULTS: Item<Vec<BatchProposalResult>> = Item::new("batch_results");
+
+/// Stores map proposal id -> chunked proposal upload in progress
+pub const UPLOADS: Map<u64, ChunkedUpload> = Map::new("uploads");
+
+/// Stores map (proposal id, chunk index) -> chunk data of the upload in progress
+pub const UPLOAD_CHUNKS: Map<(u64, u32), Binary> = Map::new("upload_chunks");
+
+/// Contains the message policy set by the owner
+pub const MESSAGE_POLICY: Item<MessagePolicy> = Item::new("message_policy");
+
+/// Contains the latest controller epoch seen by the satellite.
+/// Proposals sent in older epochs are rejected. Reset when the governance channel changes
+pub const CONTROLLER_EPOCH: Item<u64> = Item::new("controller_epoch");
+
+/// Contains the time of the latest ASTRO transfer to the hub
+pub const LAST_ASTRO_TRANSFER: Item<Timestamp> = Item::new("last_astro_transfer");
+
+/// Stores map report kind -> time when the latest report of this kind was sent
+pub const LAST_REPORTS: Map<&str, Timestamp> = Map::new("last_reports");
+
 /// Contains the time when the latest heartbeat was received from the hub
 pub const LATEST_HUB_SIGNAL_TIME: Item<Timestamp> = Item::new("latest_hub_signal_time");
 
@@ -198,3 +384,33 @@ pub const LATEST_HUB_SIGNAL_TIME: Item<Timestamp> = Item::new("latest_hub_signal
 pub fn store_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> StdResult<()> {
     RESULTS.save(deps.storage, proposal_id, &env.block.height)
 }
+
+/// Returns the protocol version negotiated for the channel
+pub fn channel_version(storage: &dyn Storage, channel_id: &str) -> StdResult<IbcVersion> {
+    Ok(CHANNEL_VERSIONS
+        .may_load(storage, channel_id)?
+        // Channels opened before the versioning was introduced
+        .unwrap_or(IbcVersion::V1))
+}
+
+/// Removes the chunked proposal upload along with the received chunks
+pub fn clear_upload(storage: &mut dyn Storage, id: u64) -> StdResult<()> {
+    let indexes = UPLOAD_CHUNKS
+        .prefix(id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for index in indexes {
+        UPLOAD_CHUNKS.remove(storage, (id, index));
+    }
+    UPLOADS.remove(storage, id);
+
+    Ok(())
+}
+
+/// Returns the highest executed proposal id
+pub fn last_executed_proposal(storage: &dyn Storage) -> StdResult<Option<u64>> {
+    RESULTS
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+}
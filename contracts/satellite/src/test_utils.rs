@@ -0,0 +1,107 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_ibc_channel, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    DepsMut, Env, IbcChannelConnectMsg, IbcOrder, MessageInfo, OwnedDeps, Response,
+};
+
+use astro_satellite_package::{ExecuteMsg, IbcVersion, InstantiateMsg, UpdateConfigMsg};
+
+use crate::contract::{execute, instantiate};
+use crate::error::ContractError;
+use crate::ibc::{ibc_channel_connect, IBC_ORDERING};
+
+pub const OWNER: &str = "owner";
+pub const CONTROLLER: &str = "controller";
+pub const GOV_CHANNEL: &str = "channel-20";
+
+pub fn mock_all(
+    sender: &str,
+) -> (
+    OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    Env,
+    MessageInfo,
+) {
+    let deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info(sender, &[]);
+    (deps, env, info)
+}
+
+pub fn init_contract(mut deps: DepsMut, env: Env, info: MessageInfo) {
+    let err = instantiate(
+        deps.branch(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            owner: OWNER.to_string(),
+            astro_denom: "".to_string(),
+            transfer_channel: "".to_string(),
+            main_controller: CONTROLLER.to_string(),
+            main_maker: "".to_string(),
+            timeout: 0,
+            max_signal_outage: 1209600,
+            emergency_owner: OWNER.to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::TimeoutLimitsError {}, err);
+
+    instantiate(
+        deps,
+        env,
+        info,
+        InstantiateMsg {
+            owner: OWNER.to_string(),
+            astro_denom: "".to_string(),
+            transfer_channel: "".to_string(),
+            main_controller: CONTROLLER.to_string(),
+            main_maker: "".to_string(),
+            timeout: 60,
+            max_signal_outage: 1209600,
+            emergency_owner: OWNER.to_string(),
+        },
+    )
+    .unwrap();
+}
+
+pub fn mock_ibc_channel_connect_ack(
+    my_channel_id: &str,
+    order: IbcOrder,
+    version: &str,
+    their_port: &str,
+) -> IbcChannelConnectMsg {
+    let mut mocked_channel = mock_ibc_channel(my_channel_id, order, version);
+    mocked_channel.counterparty_endpoint.port_id = their_port.to_string();
+    IbcChannelConnectMsg::new_ack(mocked_channel, version)
+}
+
+/// Updates the config on behalf of the owner
+pub fn update_config(deps: DepsMut, params: UpdateConfigMsg) -> Result<Response, ContractError> {
+    execute(
+        deps,
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig(params),
+    )
+}
+
+/// Connects the controller over the latest protocol version and sets up the governance channel
+pub fn connect_gov_channel(mut deps: DepsMut) {
+    let connect_msg = mock_ibc_channel_connect_ack(
+        GOV_CHANNEL,
+        IBC_ORDERING,
+        IbcVersion::LATEST.as_str(),
+        &format!("wasm.{}", CONTROLLER),
+    );
+    ibc_channel_connect(deps.branch(), mock_env(), connect_msg).unwrap();
+
+    update_config(
+        deps,
+        UpdateConfigMsg {
+            gov_channel: Some(GOV_CHANNEL.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+}
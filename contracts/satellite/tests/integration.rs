@@ -3,13 +3,28 @@ use std::rc::Rc;
 
 use astro_satellite::contract::{execute, instantiate, query, reply};
 use astro_satellite::error::ContractError;
+use astro_satellite::ibc::{ibc_channel_connect, ibc_packet_receive};
 use astro_satellite::state::Config;
-use astro_satellite_package::{ExecuteMsg, InstantiateMsg, UpdateConfigMsg};
+use astro_satellite_package::{
+    BatchProposal, BatchProposalResult, ConfigResponse, ExecuteMsg, ExecutionMode,
+    ExecutionReceipt, IbcAckResult, IbcVersion, InstantiateMsg, LastHubSignalResponse, MessageKind,
+    MessagePolicy, OwnershipProposalResponse, PolicyList, QueryMsg, SatelliteMsg, SimulationResult,
+    UpdateConfigMsg,
+};
 use astroport_mocks::{astroport_address, MockSatelliteBuilder};
+use cosmos_sdk_proto::cosmos::authz::v1beta1::MsgExec;
+use cosmos_sdk_proto::cosmos::bank::v1beta1::MsgSend;
+use cosmos_sdk_proto::cosmwasm::wasm::v1::{
+    MsgExecuteContract, MsgMigrateContract, MsgUpdateAdmin,
+};
+use cosmos_sdk_proto::Any;
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    from_json, wasm_execute, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo,
-    Response, StdResult, WasmMsg,
+    from_json, to_json_binary, wasm_execute, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty,
+    Env, IbcChannel, IbcChannelConnectMsg, IbcEndpoint, IbcOrder, IbcPacket, IbcPacketReceiveMsg,
+    IbcTimeout, MessageInfo, Response, StdResult, WasmMsg,
 };
+use prost::Message;
 
 use astroport_ibc::{SIGNAL_OUTAGE_LIMITS, TIMEOUT_LIMITS};
 use astroport_mocks::{
@@ -28,6 +43,43 @@ fn satellite_contract() -> Box<dyn Contract<Empty>> {
     Box::new(ContractWrapper::new_with_empty(execute, instantiate, query).with_reply_empty(reply))
 }
 
+/// Packets delivered to the satellite by the mocked relayer,
+/// since the multi-test app doesn't route IBC packets
+#[cw_serde]
+enum RelayMsg {
+    Connect { channel: IbcChannel },
+    Receive { packet: IbcPacket },
+}
+
+/// Calls the IBC entry points of the satellite. The replies of the packet receive run as usual
+/// and their data overrides the acknowledgement returned in the response data
+fn relay(deps: DepsMut, env: Env, msg: RelayMsg) -> Result<Response, ContractError> {
+    match msg {
+        RelayMsg::Connect { channel } => {
+            let version = channel.version.clone();
+            ibc_channel_connect(deps, env, IbcChannelConnectMsg::new_ack(channel, version))?;
+            Ok(Response::new())
+        }
+        RelayMsg::Receive { packet } => {
+            let relayer = Addr::unchecked("relayer");
+            let response = ibc_packet_receive(deps, env, IbcPacketReceiveMsg::new(packet, relayer))
+                .unwrap_or_else(|never| match never {});
+            Ok(Response::new()
+                .add_submessages(response.messages)
+                .add_attributes(response.attributes)
+                .set_data(response.acknowledgement))
+        }
+    }
+}
+
+fn relayed_satellite_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(execute, instantiate, query)
+            .with_reply_empty(reply)
+            .with_sudo_empty(relay),
+    )
+}
+
 fn noop_contract() -> Box<dyn Contract<Empty>> {
     fn noop_execute(
         _deps: DepsMut,
@@ -206,6 +258,104 @@ fn test_check_messages() {
         "Generic error: Can't check messages with a MsgGrant message"
     );
 
+    // Check MsgGrant wrapped into authz MsgExec
+    let grant = Any {
+        type_url: "/cosmos.authz.v1beta1.MsgGrant".to_string(),
+        value: vec![],
+    };
+    let err = app
+        .execute_contract(
+            satellite_addr.clone(),
+            satellite_addr.clone(),
+            &ExecuteMsg::<Empty>::CheckMessages(vec![CosmosMsg::Stargate {
+                type_url: "/cosmos.authz.v1beta1.MsgExec".to_string(),
+                value: MsgExec {
+                    grantee: satellite_addr.to_string(),
+                    msgs: vec![grant],
+                }
+                .encode_to_vec()
+                .into(),
+            }]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Can't check messages with a MsgGrant message"
+    );
+
+    // Check migration of the contract itself wrapped into authz MsgExec
+    let migrate = Any {
+        type_url: "/cosmwasm.wasm.v1.MsgMigrateContract".to_string(),
+        value: MsgMigrateContract {
+            sender: satellite_addr.to_string(),
+            contract: satellite_addr.to_string(),
+            code_id: satellite_code,
+            msg: b"{}".to_vec(),
+        }
+        .encode_to_vec(),
+    };
+    let err = app
+        .execute_contract(
+            satellite_addr.clone(),
+            satellite_addr.clone(),
+            &ExecuteMsg::<Empty>::CheckMessages(vec![CosmosMsg::Stargate {
+                type_url: "/cosmos.authz.v1beta1.MsgExec".to_string(),
+                value: MsgExec {
+                    grantee: satellite_addr.to_string(),
+                    msgs: vec![migrate],
+                }
+                .encode_to_vec()
+                .into(),
+            }]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Can't check messages with a migration or update admin message of the contract itself"
+    );
+
+    // Check update admin of the contract itself as a wasm message
+    let err = app
+        .execute_contract(
+            satellite_addr.clone(),
+            satellite_addr.clone(),
+            &ExecuteMsg::<Empty>::CheckMessages(vec![CosmosMsg::Stargate {
+                type_url: "/cosmwasm.wasm.v1.MsgUpdateAdmin".to_string(),
+                value: MsgUpdateAdmin {
+                    sender: satellite_addr.to_string(),
+                    new_admin: owner.to_string(),
+                    contract: satellite_addr.to_string(),
+                }
+                .encode_to_vec()
+                .into(),
+            }]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Can't check messages with a migration or update admin message of the contract itself"
+    );
+
+    // Malformed well-known messages are rejected
+    let err = app
+        .execute_contract(
+            satellite_addr.clone(),
+            satellite_addr.clone(),
+            &ExecuteMsg::<Empty>::CheckMessages(vec![CosmosMsg::Stargate {
+                type_url: "/cosmos.authz.v1beta1.MsgExec".to_string(),
+                value: vec![0xff].into(),
+            }]),
+            &[],
+        )
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("Error parsing into type /cosmos.authz.v1beta1.MsgExec"));
+
     // Nobody but a satellite contract can be a message sender
     let err = app
         .execute_contract(
@@ -291,10 +441,128 @@ fn test_execute_multisig() {
     app.execute_contract(
         owner.clone(),
         satellite_addr.clone(),
-        &ExecuteMsg::<Empty>::ExecuteFromMultisig(messages),
+        &ExecuteMsg::<Empty>::ExecuteFromMultisig(messages.clone()),
+        &[],
+    )
+    .unwrap();
+
+    // Only the owner can set the message policy
+    let policy = MessagePolicy {
+        kinds: PolicyList {
+            allow: vec![],
+            deny: vec![MessageKind::Bank],
+        },
+        contracts: PolicyList {
+            allow: vec![],
+            deny: vec![noop_addr.to_string()],
+        },
+        ..Default::default()
+    };
+    let err = app
+        .execute_contract(
+            random,
+            satellite_addr.clone(),
+            &ExecuteMsg::<Empty>::UpdateMessagePolicy(policy.clone()),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    app.execute_contract(
+        owner.clone(),
+        satellite_addr.clone(),
+        &ExecuteMsg::<Empty>::UpdateMessagePolicy(policy.clone()),
         &[],
     )
     .unwrap();
+    let active_policy: MessagePolicy = app
+        .wrap()
+        .query_wasm_smart(&satellite_addr, &QueryMsg::MessagePolicy {})
+        .unwrap();
+    assert_eq!(active_policy, policy);
+
+    // Messages rejected by the policy can't be executed by the multisig
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            satellite_addr.clone(),
+            &ExecuteMsg::<Empty>::ExecuteFromMultisig(messages),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MessageDenied {
+            index: 0,
+            reason: format!("contract {noop_addr} is not allowed"),
+        },
+        err.downcast().unwrap()
+    );
+
+    // Stargate forms of the messages and the messages wrapped into authz MsgExec are checked too
+    let execute_any = Any {
+        type_url: "/cosmwasm.wasm.v1.MsgExecuteContract".to_string(),
+        value: MsgExecuteContract {
+            sender: satellite_addr.to_string(),
+            contract: noop_addr.to_string(),
+            msg: b"{}".to_vec(),
+            funds: vec![],
+        }
+        .encode_to_vec(),
+    };
+    let send_any = Any {
+        type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        value: MsgSend {
+            from_address: satellite_addr.to_string(),
+            to_address: "receiver".to_string(),
+            amount: vec![],
+        }
+        .encode_to_vec(),
+    };
+    let cases = [
+        (
+            execute_any.clone(),
+            format!("contract {noop_addr} is not allowed"),
+        ),
+        (
+            Any {
+                type_url: "/cosmos.authz.v1beta1.MsgExec".to_string(),
+                value: MsgExec {
+                    grantee: satellite_addr.to_string(),
+                    msgs: vec![execute_any],
+                }
+                .encode_to_vec(),
+            },
+            format!("contract {noop_addr} is not allowed"),
+        ),
+        (
+            Any {
+                type_url: "/cosmos.authz.v1beta1.MsgExec".to_string(),
+                value: MsgExec {
+                    grantee: satellite_addr.to_string(),
+                    msgs: vec![send_any],
+                }
+                .encode_to_vec(),
+            },
+            format!("{} messages are not allowed", MessageKind::Bank),
+        ),
+    ];
+    for (any, reason) in cases {
+        let err = app
+            .execute_contract(
+                owner.clone(),
+                satellite_addr.clone(),
+                &ExecuteMsg::<Empty>::ExecuteFromMultisig(vec![CosmosMsg::Stargate {
+                    type_url: any.type_url,
+                    value: any.value.into(),
+                }]),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            ContractError::MessageDenied { index: 0, reason },
+            err.downcast().unwrap()
+        );
+    }
 }
 
 #[test]
@@ -336,6 +604,12 @@ fn test_check_update_configs() {
             timeout: None,
             emergency_owner: None,
             max_signal_outage: None,
+            timelock: None,
+            guardian: None,
+            clear_guardian: None,
+            max_proposal_age: None,
+            min_astro_transfer: None,
+            astro_transfer_cooldown: None,
         }),
         &[],
     )
@@ -350,6 +624,40 @@ fn test_check_update_configs() {
         assert_eq!("wasm.controller_addr_test", res.main_controller_port);
     }
 
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&satellite_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.main_controller_port, "wasm.controller_addr_test");
+    assert_eq!(config.gov_channel, None);
+
+    let proposal: Option<OwnershipProposalResponse> = app
+        .wrap()
+        .query_wasm_smart(&satellite_addr, &QueryMsg::OwnershipProposal {})
+        .unwrap();
+    assert_eq!(proposal, None);
+    app.execute_contract(
+        owner.clone(),
+        satellite_addr.clone(),
+        &ExecuteMsg::<Empty>::ProposeNewOwner {
+            owner: "new_owner".to_string(),
+            expires_in: 100,
+        },
+        &[],
+    )
+    .unwrap();
+    let proposal: Option<OwnershipProposalResponse> = app
+        .wrap()
+        .query_wasm_smart(&satellite_addr, &QueryMsg::OwnershipProposal {})
+        .unwrap();
+    assert_eq!(
+        proposal,
+        Some(OwnershipProposalResponse {
+            owner: Addr::unchecked("new_owner"),
+            ttl: app.block_info().time.seconds() + 100,
+        })
+    );
+
     let err = app
         .execute_contract(
             owner.clone(),
@@ -364,6 +672,12 @@ fn test_check_update_configs() {
                 timeout: None,
                 max_signal_outage: None,
                 emergency_owner: None,
+                timelock: None,
+                guardian: None,
+                clear_guardian: None,
+                max_proposal_age: None,
+                min_astro_transfer: None,
+                astro_transfer_cooldown: None,
             }),
             &[],
         )
@@ -383,6 +697,12 @@ fn test_check_update_configs() {
             timeout: None,
             max_signal_outage: None,
             emergency_owner: None,
+            timelock: None,
+            guardian: None,
+            clear_guardian: None,
+            max_proposal_age: None,
+            min_astro_transfer: None,
+            astro_transfer_cooldown: None,
         }),
         &[],
     )
@@ -428,11 +748,25 @@ fn check_ownership_capabilities() {
     assert_unauthorized(satellite.update_emergency_owner(&another_user, &another_user));
     assert_unauthorized(satellite.update_admin(&another_user));
 
+    let query_last_signal = || -> LastHubSignalResponse {
+        app.borrow()
+            .wrap()
+            .query_wasm_smart(&satellite.address, &QueryMsg::LastHubSignal {})
+            .unwrap()
+    };
+    let last_signal = query_last_signal();
+    assert_eq!(
+        last_signal.outage_deadline,
+        last_signal.time.plus_seconds(*SIGNAL_OUTAGE_LIMITS.start())
+    );
+    assert!(!last_signal.lost);
+
     // Let's check the same when signal outage is reached
     app.borrow_mut().update_block(|b| {
         b.time = b.time.plus_seconds(*SIGNAL_OUTAGE_LIMITS.start() + 1);
         b.height += 1;
     });
+    assert!(query_last_signal().lost);
 
     // The main owner still can change everything
     satellite
@@ -459,3 +793,168 @@ fn assert_unauthorized(result: AnyResult<AppResponse>) {
         ContractError::Unauthorized {}
     )
 }
+
+#[test]
+fn test_relayed_acknowledgements() {
+    let owner = Addr::unchecked("owner");
+    let mut app = mock_app(&owner, vec![]);
+
+    let noop_code = app.store_code(noop_contract());
+    let noop_addr = app
+        .instantiate_contract(noop_code, owner.clone(), &Empty {}, &[], "Noop", None)
+        .unwrap();
+
+    let satellite_code = app.store_code(relayed_satellite_contract());
+    let satellite_addr = app
+        .instantiate_contract(
+            satellite_code,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: owner.to_string(),
+                astro_denom: "none".to_string(),
+                transfer_channel: "none".to_string(),
+                main_controller: "controller".to_string(),
+                main_maker: "none".to_string(),
+                timeout: 60,
+                max_signal_outage: 1209600,
+                emergency_owner: owner.to_string(),
+            },
+            &[],
+            "Satellite label",
+            None,
+        )
+        .unwrap();
+
+    let endpoint = IbcEndpoint {
+        port_id: format!("wasm.{satellite_addr}"),
+        channel_id: "channel-0".to_string(),
+    };
+    let counterparty_endpoint = IbcEndpoint {
+        port_id: "wasm.controller".to_string(),
+        channel_id: "channel-1".to_string(),
+    };
+    app.wasm_sudo(
+        satellite_addr.clone(),
+        &RelayMsg::Connect {
+            channel: IbcChannel::new(
+                endpoint.clone(),
+                counterparty_endpoint.clone(),
+                IbcOrder::Unordered,
+                IbcVersion::LATEST.as_str(),
+                "connection-0",
+            ),
+        },
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        satellite_addr.clone(),
+        &ExecuteMsg::<Empty>::UpdateConfig(UpdateConfigMsg {
+            gov_channel: Some(endpoint.channel_id.clone()),
+            ..Default::default()
+        }),
+        &[],
+    )
+    .unwrap();
+
+    let mut sequence = 0;
+    let mut relay_packet = |app: &mut App, msg: SatelliteMsg| -> IbcAckResult {
+        sequence += 1;
+        let packet = IbcPacket::new(
+            msg.encode(IbcVersion::LATEST).unwrap(),
+            counterparty_endpoint.clone(),
+            endpoint.clone(),
+            sequence,
+            IbcTimeout::with_timestamp(app.block_info().time.plus_seconds(60)),
+        );
+        let resp = app
+            .wasm_sudo(satellite_addr.clone(), &RelayMsg::Receive { packet })
+            .unwrap();
+        from_json(resp.data.unwrap()).unwrap()
+    };
+    let noop_msg: CosmosMsg = wasm_execute(&noop_addr, &Empty {}, vec![]).unwrap().into();
+    let failing_msg: CosmosMsg = wasm_execute("unknown", &Empty {}, vec![]).unwrap().into();
+    let block = app.block_info();
+
+    // The receipt set by the contract execution is unwrapped from the reply data
+    let ack = relay_packet(
+        &mut app,
+        SatelliteMsg::ExecuteProposal {
+            id: 1,
+            messages: vec![noop_msg.clone()],
+            sent_at: Some(block.time),
+            epoch: Some(0),
+            mode: ExecutionMode::Atomic,
+        },
+    );
+    let IbcAckResult::Ok(data) = ack else {
+        panic!("Unexpected acknowledgement: {ack:?}")
+    };
+    let receipt: ExecutionReceipt = from_json(data).unwrap();
+    assert_eq!(receipt.height, block.height);
+    assert_eq!(receipt.messages.len(), 1);
+    assert_eq!(receipt.messages[0].error, None);
+
+    let ack = relay_packet(
+        &mut app,
+        SatelliteMsg::ExecuteBatch {
+            proposals: vec![
+                BatchProposal {
+                    id: 2,
+                    messages: vec![noop_msg.clone()],
+                    mode: ExecutionMode::Atomic,
+                },
+                BatchProposal {
+                    id: 3,
+                    messages: vec![noop_msg.clone(), noop_msg.clone()],
+                    mode: ExecutionMode::Atomic,
+                },
+            ],
+            sent_at: block.time,
+            epoch: 0,
+        },
+    );
+    let IbcAckResult::Ok(data) = ack else {
+        panic!("Unexpected acknowledgement: {ack:?}")
+    };
+    let results: Vec<BatchProposalResult> = from_json(data).unwrap();
+    assert_eq!(
+        results.iter().map(|result| result.id).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    for (result, messages) in results.into_iter().zip([1, 2]) {
+        let IbcAckResult::Ok(data) = result.result else {
+            panic!("Unexpected result: {:?}", result.result)
+        };
+        let receipt: ExecutionReceipt = from_json(data).unwrap();
+        assert_eq!(receipt.messages.len(), messages);
+    }
+
+    // The simulation result is unwrapped from the reply data as well
+    let ack = relay_packet(
+        &mut app,
+        SatelliteMsg::SimulateProposal {
+            id: 1,
+            messages: vec![noop_msg],
+        },
+    );
+    assert_eq!(
+        ack,
+        IbcAckResult::Ok(to_json_binary(&SimulationResult::Passed {}).unwrap())
+    );
+
+    let ack = relay_packet(
+        &mut app,
+        SatelliteMsg::SimulateProposal {
+            id: 2,
+            messages: vec![failing_msg],
+        },
+    );
+    let IbcAckResult::Ok(data) = ack else {
+        panic!("Unexpected acknowledgement: {ack:?}")
+    };
+    assert!(matches!(
+        from_json(data).unwrap(),
+        SimulationResult::Failed { .. }
+    ));
+}
@@ -1,6 +1,68 @@
+use std::fmt::{Display, Formatter};
 use std::ops::RangeInclusive;
 
+use cosmwasm_schema::cw_serde;
+
 pub const TIMEOUT_LIMITS: RangeInclusive<u64> = 60..=43200;
 
 /// 2 weeks - 2 months
 pub const SIGNAL_OUTAGE_LIMITS: RangeInclusive<u64> = 1209600..=5184000;
+
+/// IBC app version is the prefix followed by the protocol version number, e.g. `astroport-ibc-v1`
+pub const IBC_APP_VERSION_PREFIX: &str = "astroport-ibc-v";
+
+/// Versions of the protocol used between the controller and satellites
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord)]
+pub enum IbcVersion {
+    V1,
+    V2,
+}
+
+impl IbcVersion {
+    /// Supported versions in ascending order
+    pub const SUPPORTED: [IbcVersion; 2] = [IbcVersion::V1, IbcVersion::V2];
+
+    pub const LATEST: IbcVersion = IbcVersion::V2;
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IbcVersion::V1 => "astroport-ibc-v1",
+            IbcVersion::V2 => "astroport-ibc-v2",
+        }
+    }
+
+    /// Parses the IBC app version. Returns None if the version is not supported
+    pub fn parse(version: &str) -> Option<Self> {
+        Self::SUPPORTED
+            .into_iter()
+            .find(|supported| supported.as_str() == version)
+    }
+
+    /// Returns the highest supported version which doesn't exceed the proposed one.
+    /// The proposed version might be unknown to this contract, e.g. `astroport-ibc-v3`
+    pub fn negotiate(proposed: &str) -> Option<Self> {
+        let number: u32 = proposed
+            .strip_prefix(IBC_APP_VERSION_PREFIX)?
+            .parse()
+            .ok()?;
+
+        Self::SUPPORTED
+            .into_iter()
+            .rev()
+            .find(|supported| supported.number() <= number)
+    }
+
+    fn number(&self) -> u32 {
+        match self {
+            IbcVersion::V1 => 1,
+            IbcVersion::V2 => 2,
+        }
+    }
+}
+
+impl Display for IbcVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
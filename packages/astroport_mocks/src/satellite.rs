@@ -131,6 +131,12 @@ where
                 timeout: None,
                 max_signal_outage: None,
                 emergency_owner: Some(emergency_owner.to_string()),
+                timelock: None,
+                guardian: None,
+                clear_guardian: None,
+                max_proposal_age: None,
+                min_astro_transfer: None,
+                astro_transfer_cooldown: None,
             }),
             &[],
         )
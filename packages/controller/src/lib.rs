@@ -13,16 +13,362 @@ pub struct IbcProposal {
     pub messages: Vec<CosmosMsg>,
 }
 
+/// Describes a satellite registered in the controller
+#[cw_serde]
+pub struct SatelliteInfo {
+    /// Human readable alias of the remote chain, e.g. `neutron`
+    pub alias: String,
+    /// Controller's channel connected to the satellite
+    pub channel_id: String,
+    /// Satellite's IBC port, i.e. `wasm.<satellite address>`.
+    /// Must match the counterparty port of the channel
+    pub port_id: String,
+    /// Connection id the channel is built upon.
+    /// Must match the connection of the channel
+    pub connection_id: String,
+    /// Whether proposals and heartbeats can be sent to the satellite
+    pub enabled: bool,
+    /// Time in seconds after which the satellite considers itself lost.
+    /// Must match the satellite's own config
+    pub max_signal_outage: u64,
+    /// Proposal packet timeout in seconds overriding the controller's default timeout.
+    /// Useful for satellites behind slow relayer paths
+    pub timeout: Option<u64>,
+}
+
+/// Describes an IBC channel opened by the controller
+#[cw_serde]
+pub struct ChannelInfo {
+    /// Controller's channel id
+    pub channel_id: String,
+    /// Satellite's IBC port, i.e. `wasm.<satellite address>`
+    pub counterparty_port_id: String,
+    /// Satellite's channel id
+    pub counterparty_channel_id: String,
+    /// Connection id the channel is built upon
+    pub connection_id: String,
+    /// Block time at which the channel was connected
+    pub connected_at: Timestamp,
+    /// Protocol version negotiated on the channel opening
+    pub version: IbcVersion,
+}
+
+/// The latest reports received from the satellite
+#[cw_serde]
+pub struct ChannelReports {
+    /// Controller's channel the reports were received from
+    pub channel_id: String,
+    pub status: Option<StatusReport>,
+    pub balances: Option<BalanceReport>,
+    pub config: Option<ConfigSnapshot>,
+}
+
+impl ChannelReports {
+    pub fn new(channel_id: impl Into<String>) -> Self {
+        Self {
+            channel_id: channel_id.into(),
+            status: None,
+            balances: None,
+            config: None,
+        }
+    }
+}
+
+/// Liveness data collected from the packets relayed to a satellite channel
+#[cw_serde]
+#[derive(Default)]
+pub struct ChannelHealth {
+    /// Block time at which the latest heartbeat was sent
+    pub last_heartbeat_sent: Option<Timestamp>,
+    /// Block time at which the latest successful acknowledgement was received.
+    /// The satellite resets its signal outage timer on every successfully received packet
+    pub last_ack: Option<Timestamp>,
+    /// Number of packets timed out in a row since the latest acknowledgement, including error ones
+    pub consecutive_timeouts: u32,
+    /// The latest error acknowledgement received from the satellite
+    pub last_error: Option<String>,
+}
+
+#[cw_serde]
+pub struct SatelliteHealthResponse {
+    pub alias: String,
+    pub channel_id: String,
+    pub health: ChannelHealth,
+    /// Time at which the satellite enters emergency mode unless it receives a packet.
+    /// None if no packet was ever acknowledged
+    pub outage_deadline: Option<Timestamp>,
+    /// Whether the outage deadline is closer than the warning margin or unknown
+    pub nearing_outage: bool,
+}
+
+/// Describes the proposal state on a specific satellite channel
+#[cw_serde]
+pub struct ChannelProposalStatus {
+    pub channel_id: String,
+    pub status: ProposalStatus,
+}
+
+/// Describes the proposal packet relayed to a specific satellite channel
+#[cw_serde]
+pub struct ProposalDispatch {
+    /// Controller's channel the packet was sent to
+    pub channel_id: String,
+    /// Proposal state on the satellite
+    pub status: ProposalStatus,
+    /// Block height at which the packet was sent
+    pub sent_height: u64,
+    /// Block time at which the packet was sent
+    pub sent_at: Timestamp,
+    /// Timestamp after which the packet times out
+    pub timeout_at: Timestamp,
+    /// Block time at which the acknowledgement or the timeout was received
+    pub finished_at: Option<Timestamp>,
+    /// Error returned by the satellite or the timeout reason
+    pub error: Option<String>,
+    /// Number of times the packet was sent to the satellite
+    pub attempts: u32,
+    /// Whether the failed packet can be sent again via [`ExecuteMsg::RetryProposal`]
+    pub retryable: bool,
+    /// Proposal specific packet timeout in seconds. Also applied on retries
+    pub timeout: Option<u64>,
+    /// Execution details reported by the satellite acknowledging the proposal
+    pub receipt: Option<ExecutionReceipt>,
+}
+
+/// Describes an error which occurred while relaying the proposal to a satellite
+#[cw_serde]
+pub struct ProposalError {
+    pub proposal_id: u64,
+    /// Controller's channel the packet was sent to.
+    /// Empty for the error migrated from the v1.1.x `LAST_ERROR` storage item
+    /// which is kept under proposal id 0 and attempt 0
+    pub channel_id: String,
+    /// Attempt number the error relates to
+    pub attempt: u32,
+    /// Block time at which the error was received
+    pub time: Timestamp,
+    pub error: String,
+    /// Error code acknowledged by the satellite. None for timeouts and migrated errors
+    pub code: Option<AckErrorCode>,
+    /// Index of the failing proposal message if the satellite reported it
+    pub message_index: Option<u32>,
+}
+
+/// Contains everything the controller knows about the relayed proposal
+#[cw_serde]
+pub struct ProposalResponse {
+    pub id: u64,
+    /// Aggregated proposal state across all target satellites
+    pub status: ProposalStatus,
+    /// Messages relayed to the satellites
+    pub messages: Vec<CosmosMsg>,
+    /// How the satellites execute the messages
+    pub mode: ExecutionMode,
+    /// Packets sent to every target satellite
+    pub dispatches: Vec<ProposalDispatch>,
+}
+
+/// Number of proposals in each state
+#[cw_serde]
+#[derive(Default)]
+pub struct StatusCounts {
+    pub in_progress: u64,
+    pub executed: u64,
+    pub failed: u64,
+}
+
+impl StatusCounts {
+    pub fn add(&mut self, status: &ProposalStatus) {
+        match status {
+            ProposalStatus::InProgress => self.in_progress += 1,
+            ProposalStatus::Executed => self.executed += 1,
+            ProposalStatus::Failed => self.failed += 1,
+            _ => {}
+        }
+    }
+
+    pub fn remove(&mut self, status: &ProposalStatus) {
+        match status {
+            ProposalStatus::InProgress => self.in_progress -= 1,
+            ProposalStatus::Executed => self.executed -= 1,
+            ProposalStatus::Failed => self.failed -= 1,
+            _ => {}
+        }
+    }
+}
+
+/// Number of proposals in each state on a specific satellite channel
+#[cw_serde]
+pub struct ChannelStatusCounts {
+    pub channel_id: String,
+    pub counts: StatusCounts,
+}
+
+#[cw_serde]
+pub struct ProposalCountsResponse {
+    /// Aggregated proposal states
+    pub total: StatusCounts,
+    /// Proposal states per satellite channel ordered by channel id
+    pub channels: Vec<ChannelStatusCounts>,
+}
+
+/// Status of a proposal simulation on the satellite
+#[cw_serde]
+pub enum SimulationStatus {
+    /// The simulation packet is in flight
+    Pending,
+    /// Every message succeeded on the satellite
+    Passed,
+    /// The messages failed on the satellite or the packet was rejected
+    Failed { error: String },
+    /// The simulation packet timed out
+    TimedOut,
+}
+
+/// Describes a dry-run of proposal messages on the satellite
+#[cw_serde]
+pub struct SimulationInfo {
+    pub id: u64,
+    /// Satellite alias the simulation was sent to
+    pub satellite: String,
+    pub channel_id: String,
+    pub messages: Vec<CosmosMsg>,
+    pub sent_at: Timestamp,
+    pub status: SimulationStatus,
+    /// Block time at which the simulation result was received
+    pub finished_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    /// Address which is able to run IBC proposals
+    pub owner: Addr,
+    /// Default proposal packet timeout in seconds
+    pub timeout: u64,
+    /// Heartbeat packet timeout in seconds
+    pub heartbeat_timeout: u64,
+    /// Max number of times a proposal can be sent to the same satellite
+    pub max_proposal_attempts: u32,
+    /// Whether proposals failed with an error acknowledgement can be retried
+    pub retry_error_acks: bool,
+    /// Min number of seconds between two permissionless heartbeats sent to the same satellite
+    pub heartbeat_interval: u64,
+    /// Max size in bytes of the proposal messages sent in a single packet.
+    /// Larger proposals are split into chunks. 0 means chunking is disabled
+    pub max_chunk_size: u64,
+    /// Epoch carried by the proposal packets
+    pub epoch: u64,
+}
+
+/// Describes a pending request to change contract ownership
+#[cw_serde]
+pub struct OwnershipProposalResponse {
+    /// The newly proposed owner
+    pub owner: Addr,
+    /// Time until which the proposal can be claimed
+    pub ttl: u64,
+}
+
+#[cw_serde]
+pub struct UpdateConfigMsg {
+    /// Max number of times a proposal can be sent to the same satellite
+    pub max_proposal_attempts: Option<u32>,
+    /// Whether proposals failed with an error acknowledgement can be retried.
+    /// Timed out proposals can always be retried
+    pub retry_error_acks: Option<bool>,
+    /// Min number of seconds between two heartbeats sent to the same satellite
+    /// by anyone but the owner
+    pub heartbeat_interval: Option<u64>,
+    /// Heartbeat packet timeout in seconds
+    pub heartbeat_timeout: Option<u64>,
+    /// Max size in bytes of the proposal messages sent in a single packet.
+    /// Larger proposals are split into chunks. 0 disables chunking
+    pub max_chunk_size: Option<u64>,
+    /// New epoch of the proposal packets. Must be greater than the current one.
+    /// Satellites reject proposals sent in older epochs, e.g. ones still being retried.
+    /// To invalidate the proposals in flight, bump the epoch and abandon their dispatches.
+    /// Every satellite adopts the new epoch with the first proposal it receives in it.
+    /// Satellites start over from epoch 0 when their governance channel changes,
+    /// thus a newly deployed controller keeps the initial epoch
+    pub epoch: Option<u64>,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     /// Executes the IBC proposal that came from Assembly contract
     IbcExecuteProposal {
-        channel_id: String,
+        /// Alias of the registered satellite. Can be omitted if `channel_id` is set
+        #[serde(default)]
+        satellite: String,
+        /// Channel of the registered satellite.
+        /// The Assembly contract refers to satellites by their channels
+        channel_id: Option<String>,
+        proposal_id: u64,
+        messages: Vec<CosmosMsg>,
+        /// Packet timeout in seconds overriding both the satellite and the default timeouts
+        timeout: Option<u64>,
+        /// How the satellite executes the messages. Atomic by default
+        mode: Option<ExecutionMode>,
+    },
+    /// Executes the IBC proposal on several satellites at once.
+    /// Assembly is notified once every satellite has acknowledged the proposal or timed out
+    IbcExecuteProposalMulti {
+        /// Aliases of the registered satellites
+        satellites: Vec<String>,
         proposal_id: u64,
         messages: Vec<CosmosMsg>,
+        /// Packet timeout in seconds overriding both the satellite and the default timeouts
+        timeout: Option<u64>,
+        /// How the satellites execute the messages. Atomic by default
+        mode: Option<ExecutionMode>,
+    },
+    /// Executes several IBC proposals on the satellite with a single packet.
+    /// Each proposal is tracked on its own and Assembly is notified about each of them
+    /// ## Executor
+    /// Only the current owner can execute this
+    IbcExecuteBatch {
+        /// Alias of the registered satellite
+        satellite: String,
+        proposals: Vec<BatchProposal>,
+        /// Packet timeout in seconds overriding both the satellite and the default timeouts
+        timeout: Option<u64>,
+    },
+    /// Dry-runs the messages on the satellite without applying any of them.
+    /// The simulation id is returned in the response data
+    /// ## Executor
+    /// Anyone can execute this. Everyone but the owner can send one simulation
+    /// to the same satellite per hour
+    IbcSimulateProposal {
+        /// Alias of the registered satellite
+        satellite: String,
+        messages: Vec<CosmosMsg>,
+        /// Packet timeout in seconds overriding both the satellite and the default timeouts
+        timeout: Option<u64>,
     },
-    /// Updates the timeout for the IBC channel
+    /// Updates the default proposal packet timeout
     UpdateTimeout { new_timeout: u64 },
+    /// Updates contract parameters
+    /// ## Executor
+    /// Only the current owner can execute this
+    UpdateConfig(UpdateConfigMsg),
+    /// Sends the stored proposal messages to the satellite again.
+    /// Only failed proposals which haven't reached the attempts limit can be retried.
+    /// ## Executor
+    /// Only the current owner can execute this
+    RetryProposal {
+        id: u64,
+        /// Controller's channel the proposal was sent to
+        channel: String,
+    },
+    /// Gives up retrying the failed proposal on the channel.
+    /// The Assembly is notified if the proposal is complete as a result.
+    /// ## Executor
+    /// Only the current owner can execute this
+    AbandonProposal {
+        id: u64,
+        /// Controller's channel the proposal was sent to
+        channel: String,
+    },
     /// Creates a request to change contract ownership
     /// ## Executor
     /// Only the current owner can execute this.
@@ -40,19 +386,161 @@ pub enum ExecuteMsg {
     /// ## Executor
     /// Only the newly proposed owner can execute this
     ClaimOwnership {},
-    /// Sends heartbeats to specified satellites
-    SendHeartbeat { channels: Vec<String> },
+    /// Sends heartbeats to specified satellites.
+    /// ## Executor
+    /// Anyone can execute this, however, only the owner can send heartbeats
+    /// more often than the configured heartbeat interval
+    SendHeartbeat {
+        /// Aliases of the registered satellites
+        #[serde(default)]
+        satellites: Vec<String>,
+        /// Channels of the registered satellites
+        #[serde(default)]
+        channels: Vec<String>,
+    },
+    /// Sends heartbeats to every enabled satellite which hasn't received one
+    /// within the configured heartbeat interval
+    /// ## Executor
+    /// Anyone can execute this
+    SendHeartbeats {},
+    /// Adds a new satellite to the registry
+    /// ## Executor
+    /// Only the current owner can execute this
+    RegisterSatellite {
+        alias: String,
+        channel_id: String,
+        port_id: String,
+        connection_id: String,
+        max_signal_outage: u64,
+        /// Proposal packet timeout override
+        timeout: Option<u64>,
+    },
+    /// Updates the parameters of a registered satellite
+    /// ## Executor
+    /// Only the current owner can execute this
+    UpdateSatellite {
+        alias: String,
+        channel_id: Option<String>,
+        port_id: Option<String>,
+        connection_id: Option<String>,
+        enabled: Option<bool>,
+        max_signal_outage: Option<u64>,
+        /// Sets the proposal packet timeout override
+        timeout: Option<u64>,
+        /// Removes the proposal packet timeout override so the default timeout is used.
+        /// Must not be combined with `timeout`
+        clear_timeout: Option<bool>,
+    },
+    /// Removes the satellite from the registry
+    /// ## Executor
+    /// Only the current owner can execute this
+    RemoveSatellite { alias: String },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    /// Returns the contract config
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// Returns the pending request to change contract ownership if any
+    #[returns(Option<OwnershipProposalResponse>)]
+    OwnershipProposal {},
+
+    /// Returns the aggregated proposal state across all target satellites
     #[returns(ProposalStatus)]
     ProposalState { id: u64 },
 
+    /// Returns liveness data of the registered satellites ordered by alias.
+    /// Satellites are flagged when their outage deadline is closer than `warning_margin` seconds
+    #[returns(Vec<SatelliteHealthResponse>)]
+    SatelliteHealth {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        warning_margin: Option<u64>,
+    },
+
+    /// Returns the full proposal dispatch record
+    #[returns(ProposalResponse)]
+    Proposal { id: u64 },
+
+    /// Returns proposals ordered by id.
+    /// Can be filtered by the aggregated proposal state and by the target satellite channel
+    #[returns(Vec<ProposalResponse>)]
+    Proposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        status: Option<ProposalStatus>,
+        channel: Option<String>,
+    },
+
+    /// Returns the number of proposals in each state overall and per satellite channel
+    #[returns(ProposalCountsResponse)]
+    ProposalCounts {},
+
+    /// Returns the proposal state on every target satellite channel
+    #[returns(Vec<ChannelProposalStatus>)]
+    ProposalChannels { id: u64 },
+
+    /// Returns the error of the latest acknowledged proposal or an empty string
+    /// if it was executed successfully
     #[returns(String)]
     LastError {},
+
+    /// Returns all errors which occurred while relaying the proposal
+    #[returns(Vec<ProposalError>)]
+    ProposalError { id: u64 },
+
+    /// Returns errors of all proposals ordered by (proposal id, channel id, attempt)
+    #[returns(Vec<ProposalError>)]
+    Errors {
+        start_after: Option<(u64, String, u32)>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the satellite registered under the given alias
+    #[returns(SatelliteInfo)]
+    Satellite { alias: String },
+
+    /// Returns the list of registered satellites ordered by alias
+    #[returns(Vec<SatelliteInfo>)]
+    Satellites {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns all channels opened by the controller ordered by channel id
+    #[returns(Vec<ChannelInfo>)]
+    Channels {},
+
+    /// Returns the latest reports received from the satellite channel
+    #[returns(ChannelReports)]
+    Reports { channel_id: String },
+
+    /// Returns the latest reports received from every satellite channel ordered by channel id
+    #[returns(Vec<ChannelReports>)]
+    AllReports {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the proposal simulation by its id
+    #[returns(SimulationInfo)]
+    Simulation { id: u64 },
+
+    /// Returns proposal simulations ordered by id
+    #[returns(Vec<SimulationInfo>)]
+    Simulations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
+use astro_satellite_package::{
+    AckErrorCode, BalanceReport, ConfigSnapshot, ExecutionReceipt, StatusReport,
+};
+pub use astro_satellite_package::{BatchProposal, ExecutionMode};
 pub use astroport_governance;
-use cosmwasm_std::CosmosMsg;
+use astroport_ibc::IbcVersion;
+use cosmwasm_std::{Addr, CosmosMsg, Timestamp};
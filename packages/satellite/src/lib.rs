@@ -1,6 +1,13 @@
+use std::fmt::{Display, Formatter};
+
 use astroport_governance::assembly::ProposalStatus;
+pub use astroport_ibc::IbcVersion;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, CosmosMsg, CustomMsg, Empty};
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_string, to_json_vec, Addr, Binary, BlockInfo, Coin,
+    CosmosMsg, CustomMsg, Empty, StdError, StdResult, SubMsgResponse, Timestamp, Uint128,
+};
+use sha2::{Digest, Sha256};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -23,6 +30,7 @@ pub struct InstantiateMsg {
 }
 
 #[cw_serde]
+#[derive(Default)]
 pub struct UpdateConfigMsg {
     pub astro_denom: Option<String>,
     pub gov_channel: Option<String>,
@@ -33,12 +41,42 @@ pub struct UpdateConfigMsg {
     pub timeout: Option<u64>,
     pub max_signal_outage: Option<u64>,
     pub emergency_owner: Option<String>,
+    /// Delay in seconds between the proposal receipt and its execution. 0 disables the timelock.
+    /// Proposals received over v1 governance channels are refused while the timelock is enabled
+    pub timelock: Option<u64>,
+    /// Address which is able to veto queued proposals along with the emergency owner
+    pub guardian: Option<String>,
+    /// Removes the guardian
+    pub clear_guardian: Option<bool>,
+    /// Time in seconds after which a proposal sent by the hub is rejected. 0 disables the check.
+    /// Must be within 1 minute - 2 weeks otherwise
+    pub max_proposal_age: Option<u64>,
+    /// The smallest amount of ASTRO which can be transferred to the hub
+    pub min_astro_transfer: Option<Uint128>,
+    /// Time in seconds between ASTRO transfers to the hub. 0 disables the cooldown.
+    /// Can't exceed 1 week
+    pub astro_transfer_cooldown: Option<u64>,
 }
 
 #[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg<M: CustomMsg = Empty> {
-    TransferAstro {},
+    /// Transfers ASTRO to the maker on the main chain
+    /// ## Executor
+    /// Anyone can execute this. Only the owner can set the amount, the memo or the receiver
+    TransferAstro {
+        /// Amount to transfer. Defaults to the whole ASTRO balance
+        amount: Option<Uint128>,
+        /// ICS-20 memo attached to the transfer
+        memo: Option<String>,
+        /// Receiver on the main chain. Defaults to the maker
+        receiver: Option<String>,
+    },
     UpdateConfig(UpdateConfigMsg),
+    /// Replaces the message policy
+    /// ## Executor
+    /// Only the current owner can execute this
+    UpdateMessagePolicy(MessagePolicy),
     CheckMessages(Vec<CosmosMsg<M>>),
     ExecuteFromMultisig(Vec<CosmosMsg<M>>),
     CheckMessagesPassed {},
@@ -61,19 +99,684 @@ pub enum ExecuteMsg<M: CustomMsg = Empty> {
     ClaimOwnership {},
     /// It sets the emergency owner as admin of the contract to migrate it if the satellite is lost
     SetEmergencyOwnerAsAdmin {},
+    /// Sends the report to the controller over the governance channel
+    /// ## Executor
+    /// Anyone can execute this. Reports of the same kind can be sent by anyone but the owner
+    /// once an hour
+    SendReport {
+        kind: ReportKind,
+    },
+    /// Executes the queued proposal once its timelock has expired
+    /// ## Executor
+    /// Anyone can execute this
+    ExecuteQueued {
+        id: u64,
+    },
+    /// Cancels the queued proposal before its timelock expires
+    /// ## Executor
+    /// Only the guardian or the emergency owner can execute this
+    VetoQueued {
+        id: u64,
+    },
+    /// Executes the messages of the queued proposal
+    /// ## Executor
+    /// Only the contract itself can execute this
+    RunQueued {
+        id: u64,
+    },
+    /// Executes the messages of the batched or simulated proposal
+    /// ## Executor
+    /// Only the contract itself can execute this
+    RunBatched {
+        id: u64,
+        messages: Vec<CosmosMsg<M>>,
+        #[serde(default)]
+        mode: ExecutionMode,
+    },
+    /// Executes the simulated proposal messages in a submessage and reverts them.
+    /// Fails if the messages passed, otherwise returns the [`SimulationResult`] as data
+    /// ## Executor
+    /// Only the contract itself can execute this
+    RunSimulation {
+        id: u64,
+        messages: Vec<CosmosMsg<M>>,
+    },
+    /// Removes the chunked proposal upload which wasn't completed in time
+    /// ## Executor
+    /// Anyone can execute this
+    ClearExpiredUpload {
+        id: u64,
+    },
+}
+
+/// Acknowledgement data of the proposal queued by the satellite timelock.
+/// Sent over `astroport-ibc-v2` channels only
+pub const QUEUED_ACK: &[u8] = b"queued";
+
+/// Acknowledgement data of the proposal chunk which didn't complete the proposal upload
+pub const CHUNK_ACK: &[u8] = b"chunk";
+
+/// Result of the proposal simulated by the satellite.
+/// Carried by the simulation acknowledgement as JSON in `IbcAckResult::Ok`
+#[cw_serde]
+pub enum SimulationResult {
+    Passed {},
+    Failed { error: String },
+}
+
+/// Kinds of the messages the satellite message policy refers to
+#[cw_serde]
+pub enum MessageKind {
+    Bank,
+    Custom,
+    Staking,
+    Distribution,
+    Stargate,
+    Ibc,
+    Wasm,
+    Gov,
+}
+
+impl Display for MessageKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageKind::Bank => f.write_str("bank"),
+            MessageKind::Custom => f.write_str("custom"),
+            MessageKind::Staking => f.write_str("staking"),
+            MessageKind::Distribution => f.write_str("distribution"),
+            MessageKind::Stargate => f.write_str("stargate"),
+            MessageKind::Ibc => f.write_str("ibc"),
+            MessageKind::Wasm => f.write_str("wasm"),
+            MessageKind::Gov => f.write_str("gov"),
+        }
+    }
+}
+
+/// Allow and deny lists of the message policy.
+/// A value is rejected if it is denied or if the allow list is set and doesn't contain it
+#[cw_serde]
+pub struct PolicyList<T> {
+    /// Values which are allowed. Empty list allows every value which isn't denied
+    pub allow: Vec<T>,
+    /// Values which are denied
+    pub deny: Vec<T>,
+}
+
+impl<T> Default for PolicyList<T> {
+    fn default() -> Self {
+        Self {
+            allow: vec![],
+            deny: vec![],
+        }
+    }
+}
+
+impl<T: PartialEq> PolicyList<T> {
+    pub fn permits(&self, value: &T) -> bool {
+        !self.deny.contains(value) && (self.allow.is_empty() || self.allow.contains(value))
+    }
+}
+
+/// Restricts messages executed by the satellite, both the ones relayed from the hub and
+/// the ones sent by the multisig. The default policy allows every message.
+/// Messages wrapped by authz `MsgExec` and gov proposals, as well as the Stargate forms of
+/// the wasm and bank messages, are checked as if they were sent directly. Other Stargate
+/// messages are checked by their type url only, thus they bypass the contract and denom lists
+#[cw_serde]
+#[derive(Default)]
+pub struct MessagePolicy {
+    /// Kinds of the messages
+    pub kinds: PolicyList<MessageKind>,
+    /// Type urls of the Stargate messages
+    pub type_urls: PolicyList<String>,
+    /// Contracts executed, migrated or administered by the wasm messages
+    pub contracts: PolicyList<String>,
+    /// Denoms of the funds sent by the messages
+    pub denoms: PolicyList<String>,
+}
+
+/// Describes the chunked proposal upload in progress
+#[cw_serde]
+pub struct ChunkedUpload {
+    pub id: u64,
+    /// Hex encoded SHA-256 hash of the JSON encoded proposal messages
+    pub hash: String,
+    /// Number of chunks the proposal was split into
+    pub total: u32,
+    /// Number of chunks received so far
+    pub received: u32,
+    /// Time when the first chunk was received
+    pub started_at: Timestamp,
+    /// Time after which the upload is abandoned
+    pub expires_at: Timestamp,
+}
+
+/// Describes the state of the proposal queued by the satellite timelock
+#[cw_serde]
+pub enum QueueStatus {
+    Queued,
+    Executed,
+    Failed { error: String },
+    Vetoed,
+}
+
+impl Display for QueueStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueueStatus::Queued => f.write_str("queued"),
+            QueueStatus::Executed => f.write_str("executed"),
+            QueueStatus::Failed { .. } => f.write_str("failed"),
+            QueueStatus::Vetoed => f.write_str("vetoed"),
+        }
+    }
 }
 
+#[cw_serde]
+pub struct QueuedProposal {
+    pub id: u64,
+    pub messages: Vec<CosmosMsg>,
+    /// Time when the proposal was received
+    pub queued_at: Timestamp,
+    /// Time after which the proposal can be executed
+    pub eta: Timestamp,
+    pub status: QueueStatus,
+    #[serde(default)]
+    pub mode: ExecutionMode,
+}
+
+/// Kinds of reports the satellite is able to send to the controller
+#[cw_serde]
+pub enum ReportKind {
+    Status,
+    Balances,
+    Config,
+}
+
+impl Display for ReportKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportKind::Status => f.write_str("status"),
+            ReportKind::Balances => f.write_str("balances"),
+            ReportKind::Config => f.write_str("config"),
+        }
+    }
+}
+
+/// Packets sent by the controller to the satellite. The schema of the latest protocol version
 #[cw_serde]
 pub enum SatelliteMsg {
+    ExecuteProposal {
+        id: u64,
+        messages: Vec<CosmosMsg>,
+        /// Hub's block time at which the packet was sent. Not available over v1 channels
+        sent_at: Option<Timestamp>,
+        /// Controller epoch the proposal was sent in. Not available over v1 channels,
+        /// where proposals belong to the initial epoch 0
+        epoch: Option<u64>,
+        /// Only atomic proposals can be sent over v1 channels
+        #[serde(default)]
+        mode: ExecutionMode,
+    },
+    Heartbeat {},
+    /// Executes several proposals independently of each other.
+    /// Available since `astroport-ibc-v2`
+    ExecuteBatch {
+        proposals: Vec<BatchProposal>,
+        /// Hub's block time at which the packet was sent
+        sent_at: Timestamp,
+        /// Controller epoch the proposals were sent in
+        epoch: u64,
+    },
+    /// Executes the proposal messages without committing them to check whether they pass.
+    /// Available since `astroport-ibc-v2`
+    SimulateProposal {
+        /// Simulation id assigned by the controller
+        id: u64,
+        messages: Vec<CosmosMsg>,
+    },
+    /// Part of the proposal which is too large to be sent in a single packet.
+    /// The proposal is executed once every chunk has arrived.
+    /// Available since `astroport-ibc-v2`
+    ExecuteChunk {
+        id: u64,
+        /// Zero-based position of the chunk
+        index: u32,
+        /// Number of chunks the proposal was split into
+        total: u32,
+        /// Hex encoded SHA-256 hash of the JSON encoded proposal messages
+        hash: String,
+        data: Binary,
+        /// Hub's block time at which the packet was sent
+        sent_at: Timestamp,
+        /// Controller epoch the proposal was sent in
+        epoch: u64,
+        #[serde(default)]
+        mode: ExecutionMode,
+        /// Controller's dispatch attempt the chunk was sent in.
+        /// Lets the controller ignore outcomes of chunks sent before the proposal was retried
+        #[serde(default)]
+        attempt: u32,
+    },
+}
+
+/// Describes how the satellite executes the proposal messages
+#[cw_serde]
+#[derive(Default)]
+pub enum ExecutionMode {
+    /// Every message must succeed, otherwise the whole proposal is reverted
+    #[default]
+    Atomic,
+    /// Every message is executed on its own. Failed messages are reverted and recorded
+    /// while the rest of the proposal is kept
+    BestEffort,
+}
+
+#[cw_serde]
+pub struct BatchProposal {
+    pub id: u64,
+    pub messages: Vec<CosmosMsg>,
+    #[serde(default)]
+    pub mode: ExecutionMode,
+}
+
+/// Result of the batched proposal. The batch acknowledgement carries
+/// the JSON encoded list of these results in `IbcAckResult::Ok`
+#[cw_serde]
+pub struct BatchProposalResult {
+    pub id: u64,
+    pub result: IbcAckResult,
+}
+
+/// Stable codes of the errors acknowledged by the satellite.
+/// Codes introduced by newer satellites are decoded as [`AckErrorCode::Unknown`]
+#[cw_serde]
+pub enum AckErrorCode {
+    /// The satellite governance channel is not set yet
+    GovernanceNotEstablished,
+    /// The packet was received from a channel other than the governance one
+    InvalidChannel,
+    /// The packet can't be decoded with the channel protocol version
+    InvalidPacket,
+    /// The channel protocol version is not supported by the satellite
+    UnknownVersion,
+    /// The packet type is not supported by the channel protocol version
+    UnsupportedPacket,
+    /// The proposal is older than the satellite max proposal age
+    StaleProposal,
+    /// The proposal is already waiting in the satellite timelock queue
+    AlreadyQueued,
+    /// The proposal chunk is out of range or doesn't match the upload
+    InvalidChunk,
+    /// One of the proposal messages failed
+    MessageFailed,
+    /// One of the proposal messages is rejected by the satellite message policy
+    PolicyViolation,
+    /// The proposal was already executed by the satellite
+    AlreadyExecuted,
+    /// The proposal was sent in a controller epoch which is already superseded
+    SupersededEpoch,
+    /// The error wasn't classified, e.g. it was acknowledged by a v1 satellite
+    #[serde(other)]
+    Unknown,
+}
+
+/// Structured error acknowledged by the satellite over channels newer than `astroport-ibc-v1`.
+/// Carried as JSON text in `IbcAckResult::Error`, so that older controllers can still parse it
+#[cw_serde]
+pub struct AckError {
+    pub code: AckErrorCode,
+    /// Index of the failing proposal message if it is known
+    pub message_index: Option<u32>,
+    /// Raw error text
+    pub error: String,
+}
+
+impl AckError {
+    pub fn new(code: AckErrorCode, error: impl Display) -> Self {
+        Self {
+            code,
+            message_index: None,
+            error: error.to_string(),
+        }
+    }
+
+    pub fn message_failed(index: u32, error: impl Display) -> Self {
+        Self {
+            code: AckErrorCode::MessageFailed,
+            message_index: Some(index),
+            error: error.to_string(),
+        }
+    }
+
+    /// Encodes the error as the `IbcAckResult::Error` text
+    pub fn encode(&self) -> StdResult<String> {
+        to_json_string(self)
+    }
+
+    /// Decodes the `IbcAckResult::Error` text.
+    /// Plain text errors are kept as is with the unknown code
+    pub fn decode(error: &str) -> Self {
+        from_json(error.as_bytes()).unwrap_or_else(|_| Self::new(AckErrorCode::Unknown, error))
+    }
+}
+
+/// Result of a single proposal message executed on the satellite
+#[cw_serde]
+pub struct MessageResult {
+    /// Data returned by the message
+    pub data: Option<Binary>,
+    /// Hex encoded SHA-256 hash of the JSON encoded events emitted by the message
+    pub events_hash: String,
+    /// Error the message failed with. Only best-effort proposals keep failed messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl MessageResult {
+    pub fn new(response: SubMsgResponse) -> StdResult<Self> {
+        Ok(Self {
+            data: response.data,
+            events_hash: content_hash(&to_json_vec(&response.events)?),
+            error: None,
+        })
+    }
+
+    /// Describes the reverted message of the best-effort proposal
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            data: None,
+            events_hash: String::new(),
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Describes the successful proposal execution on the satellite.
+/// Carried in `IbcAckResult::Ok` over channels newer than `astroport-ibc-v1`
+#[cw_serde]
+pub struct ExecutionReceipt {
+    /// Satellite's block height at which the proposal was executed
+    pub height: u64,
+    /// Satellite's block time at which the proposal was executed
+    pub time: Timestamp,
+    /// Results of the proposal messages in the order they were executed
+    pub messages: Vec<MessageResult>,
+}
+
+impl ExecutionReceipt {
+    pub fn new(block: &BlockInfo, messages: Vec<MessageResult>) -> Self {
+        Self {
+            height: block.height,
+            time: block.time,
+            messages,
+        }
+    }
+
+    /// Returns indexes of the failed messages of the best-effort proposal
+    pub fn failed_messages(&self) -> Vec<u32> {
+        self.messages
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.error.is_some())
+            .map(|(index, _)| index as u32)
+            .collect()
+    }
+}
+
+impl SatelliteMsg {
+    /// Splits the JSON encoded proposal messages into chunks of at most `chunk_size` bytes
+    pub fn chunks(
+        id: u64,
+        messages: &[CosmosMsg],
+        chunk_size: usize,
+        sent_at: Timestamp,
+        epoch: u64,
+        mode: ExecutionMode,
+        attempt: u32,
+    ) -> StdResult<Vec<Self>> {
+        let content = to_json_vec(messages)?;
+        let hash = content_hash(&content);
+        let chunks: Vec<_> = content.chunks(chunk_size.max(1)).collect();
+        let total = chunks.len() as u32;
+
+        Ok(chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| SatelliteMsg::ExecuteChunk {
+                id,
+                index: index as u32,
+                total,
+                hash: hash.clone(),
+                data: data.into(),
+                sent_at,
+                epoch,
+                mode: mode.clone(),
+                attempt,
+            })
+            .collect())
+    }
+
+    /// Serializes the packet with the schema of the channel protocol version
+    pub fn encode(&self, version: IbcVersion) -> StdResult<Binary> {
+        match version {
+            IbcVersion::V1 => to_json_binary(&SatelliteMsgV1::try_from(self.clone())?),
+            IbcVersion::V2 => to_json_binary(self),
+        }
+    }
+
+    /// Deserializes the packet with the schema of the channel protocol version
+    pub fn decode(data: &[u8], version: IbcVersion) -> StdResult<Self> {
+        match version {
+            IbcVersion::V1 => from_json::<SatelliteMsgV1>(data).map(Into::into),
+            IbcVersion::V2 => from_json(data),
+        }
+    }
+}
+
+/// Returns the hex encoded SHA-256 hash of the content
+pub fn content_hash(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// Packets sent by the controller to the satellite over `astroport-ibc-v1` channels.
+/// Must never be changed as live v1 channels rely on it
+#[cw_serde]
+pub enum SatelliteMsgV1 {
     ExecuteProposal { id: u64, messages: Vec<CosmosMsg> },
     Heartbeat {},
 }
 
+impl From<SatelliteMsgV1> for SatelliteMsg {
+    fn from(msg: SatelliteMsgV1) -> Self {
+        match msg {
+            SatelliteMsgV1::ExecuteProposal { id, messages } => SatelliteMsg::ExecuteProposal {
+                id,
+                messages,
+                sent_at: None,
+                epoch: None,
+                mode: ExecutionMode::Atomic,
+            },
+            SatelliteMsgV1::Heartbeat {} => SatelliteMsg::Heartbeat {},
+        }
+    }
+}
+
+impl TryFrom<SatelliteMsg> for SatelliteMsgV1 {
+    type Error = StdError;
+
+    fn try_from(msg: SatelliteMsg) -> StdResult<Self> {
+        match msg {
+            SatelliteMsg::ExecuteProposal {
+                mode: ExecutionMode::BestEffort,
+                ..
+            } => Err(StdError::generic_err(format!(
+                "Best-effort execution is not supported by {}",
+                IbcVersion::V1
+            ))),
+            SatelliteMsg::ExecuteProposal { id, messages, .. } => {
+                Ok(SatelliteMsgV1::ExecuteProposal { id, messages })
+            }
+            SatelliteMsg::Heartbeat {} => Ok(SatelliteMsgV1::Heartbeat {}),
+            SatelliteMsg::ExecuteBatch { .. } => Err(StdError::generic_err(format!(
+                "Batches are not supported by {}",
+                IbcVersion::V1
+            ))),
+            SatelliteMsg::ExecuteChunk { .. } => Err(StdError::generic_err(format!(
+                "Chunks are not supported by {}",
+                IbcVersion::V1
+            ))),
+            SatelliteMsg::SimulateProposal { .. } => Err(StdError::generic_err(format!(
+                "Simulations are not supported by {}",
+                IbcVersion::V1
+            ))),
+        }
+    }
+}
+
+/// Packets sent by the satellite to the controller.
+/// Available since `astroport-ibc-v2`
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
+pub enum SatelliteReport {
+    Status(StatusReport),
+    Balances(BalanceReport),
+    Config(ConfigSnapshot),
+    ProposalOutcome(ProposalOutcomeReport),
+}
+
+/// Describes the final state of the proposal queued by the satellite timelock
+#[cw_serde]
+pub struct ProposalOutcomeReport {
+    /// Satellite's block time at which the proposal was executed or vetoed
+    pub time: Timestamp,
+    pub id: u64,
+    pub status: QueueStatus,
+}
+
+/// Describes the satellite liveness and governance progress
+#[cw_serde]
+pub struct StatusReport {
+    /// Satellite's block time at which the report was made
+    pub time: Timestamp,
+    /// Satellite's contract version
+    pub version: String,
+    /// Time when the latest packet was received from the hub
+    pub latest_hub_signal_time: Timestamp,
+    /// The highest executed proposal id
+    pub last_executed_proposal: Option<u64>,
+}
+
+/// Describes the satellite balances
+#[cw_serde]
+pub struct BalanceReport {
+    /// Satellite's block time at which the report was made
+    pub time: Timestamp,
+    pub balances: Vec<Coin>,
+}
+
+/// Describes the satellite config
+#[cw_serde]
+pub struct ConfigSnapshot {
+    /// Satellite's block time at which the report was made
+    pub time: Timestamp,
+    pub owner: Addr,
+    pub emergency_owner: Addr,
+    pub max_signal_outage: u64,
+    pub astro_denom: String,
+    pub main_controller_port: String,
+    pub main_maker: String,
+    pub gov_channel: Option<String>,
+    pub transfer_channel: String,
+    pub timeout: u64,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    /// Address which is able to update contracts' parameters
+    pub owner: Addr,
+    /// An address that can migrate the contract and change its config if the satellite is lost
+    pub emergency_owner: Addr,
+    /// Time in seconds after which the satellite considers itself lost
+    pub max_signal_outage: u64,
+    /// ASTRO denom on the remote chain.
+    pub astro_denom: String,
+    /// Controller contract port on the main chain.
+    pub main_controller_port: String,
+    /// Maker address on the main chain
+    pub main_maker: String,
+    /// Channel used to interact with assembly contract on the main chain.
+    pub gov_channel: Option<String>,
+    /// Channel used to transfer Astro tokens
+    pub transfer_channel: String,
+    /// when packet times out, measured on remote chain
+    pub timeout: u64,
+    /// Delay in seconds between the proposal receipt and its execution
+    pub timelock: u64,
+    /// Address which is able to veto queued proposals along with the emergency owner
+    pub guardian: Option<Addr>,
+    /// Time in seconds after which a proposal sent by the hub is rejected
+    pub max_proposal_age: u64,
+    /// The smallest amount of ASTRO which can be transferred to the hub
+    pub min_astro_transfer: Uint128,
+    /// Time in seconds between ASTRO transfers to the hub
+    pub astro_transfer_cooldown: u64,
+}
+
+/// Describes a pending request to change contract ownership
+#[cw_serde]
+pub struct OwnershipProposalResponse {
+    /// The newly proposed owner
+    pub owner: Addr,
+    /// Time until which the proposal can be claimed
+    pub ttl: u64,
+}
+
+#[cw_serde]
+pub struct LastHubSignalResponse {
+    /// Time when the latest packet was received from the hub
+    pub time: Timestamp,
+    /// Time after which the satellite considers itself lost
+    pub outage_deadline: Timestamp,
+    /// Whether the emergency owner is able to take over the satellite
+    pub lost: bool,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(ProposalStatus)]
     ProposalState { id: u64 },
+    /// Returns the contract config
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Returns the pending request to change contract ownership if any
+    #[returns(Option<OwnershipProposalResponse>)]
+    OwnershipProposal {},
+    /// Returns the time of the latest packet received from the hub
+    #[returns(LastHubSignalResponse)]
+    LastHubSignal {},
+    /// Returns the proposal queued by the timelock
+    #[returns(QueuedProposal)]
+    QueuedProposal { id: u64 },
+    /// Returns proposals queued by the timelock ordered by id
+    #[returns(Vec<QueuedProposal>)]
+    QueuedProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the chunked proposal upload in progress if any
+    #[returns(Option<ChunkedUpload>)]
+    ChunkedUpload { id: u64 },
+    /// Returns the latest controller epoch seen by the satellite.
+    /// The epoch starts over from 0 whenever the governance channel changes
+    #[returns(u64)]
+    ControllerEpoch {},
+    /// Returns the active message policy
+    #[returns(MessagePolicy)]
+    MessagePolicy {},
+    /// Returns results of the best-effort proposal messages ordered by index
+    #[returns(Vec<MessageResult>)]
+    MessageResults { id: u64 },
 }
 
 /// This is a generic ICS acknowledgement format.